@@ -0,0 +1,160 @@
+//! # Simulink C Rust wrapper macros
+//!
+//! Procedural macros to wrap generated C code from Simulink in Rust,
+//! they are re-exported by `simulink-rs`
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use simulink_model::{Model, Options};
+use std::{env, path::PathBuf};
+use syn::{
+    parenthesized,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Ident, LitBool, LitStr, Token,
+};
+
+/// Macro arguments
+struct Args {
+    sys: LitStr,
+    options: Options,
+    bindings: bool,
+    recursive: bool,
+}
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let sys: LitStr = input.parse()?;
+        let mut options = Options::default();
+        let mut bindings = true;
+        let mut recursive = false;
+        while input.parse::<Option<Token![,]>>()?.is_some() {
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            match key.to_string().as_str() {
+                "name" => {
+                    input.parse::<Token![=]>()?;
+                    options.rs_type = Some(input.parse::<LitStr>()?.value());
+                }
+                "derive" => {
+                    let content;
+                    parenthesized!(content in input);
+                    options
+                        .derives
+                        .extend(Punctuated::<syn::Path, Token![,]>::parse_terminated(
+                            &content,
+                        )?);
+                }
+                "bindings" => {
+                    input.parse::<Token![=]>()?;
+                    bindings = input.parse::<LitBool>()?.value;
+                }
+                "recursive" => {
+                    input.parse::<Token![=]>()?;
+                    recursive = input.parse::<LitBool>()?.value;
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        "expected one of `name`, `derive`, `bindings` or `recursive`",
+                    ))
+                }
+            }
+        }
+        Ok(Self {
+            sys,
+            options,
+            bindings,
+            recursive,
+        })
+    }
+}
+
+/// Expands to the Rust wrapper of a Simulink C controller
+///
+/// The argument is the path, relative to the crate root directory, of the folder with the C header
+/// and source files, followed by optional settings:
+///  * `name = "..."`: the name of the Rust type alias of the controller,
+///  * `derive(...)`: extra derive macros of the controller,
+///  * `bindings = false`: do not include the bindings written by `simulink_rs::Sys::compile`
+///    into `OUT_DIR`,
+///  * `recursive = true`: search the sub-folders for header files.
+///
+/// The C code must still be compiled by the build script, e.g.
+/// ```ignore
+/// fn main() {
+///     simulink_rs::Sys::new(Some("MySimulinkController")).compile();
+/// }
+/// ```
+/// If the build script compiles the same folder, the C files resolved by the
+/// `simulink_rs::Builder`, e.g. with glob patterns, are used and `recursive` is ignored.
+///
+/// # Example
+/// ```ignore
+/// simulink_rs::include_model!("sys", name = "MySimulinkController", derive(PartialEq));
+/// ```
+#[proc_macro]
+pub fn include_model(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as Args);
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let sys = root.join(args.sys.value());
+
+    // the files resolved by the build script, if it compiles the same folder
+    let (headers, sources) = simulink_model::resolved_files(&sys)
+        .unwrap_or_else(|| simulink_model::c_files(&sys, args.recursive));
+
+    let Some(header) = simulink_model::main_header(&headers, &sources) else {
+        return syn::Error::new(
+            args.sys.span(),
+            format!("cannot find the main header file in {:?}", sys),
+        )
+        .to_compile_error()
+        .into();
+    };
+    let model = match Model::from_files(header, &headers, &sources) {
+        Ok(model) => model,
+        Err(e) => {
+            return syn::Error::new(
+                args.sys.span(),
+                format!("failed to parse {:?}: {}", header, e),
+            )
+            .to_compile_error()
+            .into()
+        }
+    };
+
+    let module = format_ident!("__simulink_{}", model.name);
+    // recompiles the crate when a header file is modified
+    let tracked = headers
+        .iter()
+        .filter_map(|header| header.to_str())
+        .map(|header| {
+            quote!(
+                const _: &[u8] = include_bytes!(#header);
+            )
+        });
+    let bindings = args
+        .bindings
+        .then(|| quote!(include!(concat!(env!("OUT_DIR"), "/bindings.rs"));));
+    let wrapper = model.wrapper(&args.options);
+    quote! {
+        #[allow(
+            non_upper_case_globals,
+            non_camel_case_types,
+            non_snake_case,
+            improper_ctypes,
+            dead_code,
+            unused_imports
+        )]
+        mod #module {
+            use super::*;
+            #(#tracked)*
+            #bindings
+            #wrapper
+        }
+        pub use #module::*;
+    }
+    .into()
+}
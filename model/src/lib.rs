@@ -0,0 +1,666 @@
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    fmt::{Debug, Display},
+    fs,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+mod simulink;
+pub use simulink::{List, Simulink, IO};
+mod signals;
+pub use signals::snake_case;
+
+/// Simulink model description
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Model {
+    pub name: String,
+    pub simulink: Vec<Simulink>,
+    pub symbols: Symbols,
+    pub metadata: Metadata,
+    /// base sample time in seconds
+    pub sample_time: Option<f64>,
+}
+
+/// Simulink model code generation metadata
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Metadata {
+    pub model_version: Option<String>,
+    pub coder_version: Option<String>,
+    pub generated_on: Option<String>,
+    pub target: Option<String>,
+}
+impl Metadata {
+    /// Parses the header comment of the main header file
+    pub fn parse(code: &str) -> Self {
+        let regex = Regex::new(
+            r"(?m)^\s*\*\s*(Model version|Simulink Coder version|C/C\+\+ source code generated on|Target selection)\s*:\s*(.+?)\s*$",
+        )
+        .unwrap();
+        let mut metadata = Self::default();
+        for caps in regex.captures_iter(code) {
+            let value = Some(caps[2].to_string());
+            match &caps[1] {
+                "Model version" => metadata.model_version = value,
+                "Simulink Coder version" => metadata.coder_version = value,
+                "Target selection" => metadata.target = value,
+                _ => metadata.generated_on = value,
+            }
+        }
+        metadata
+    }
+}
+
+/// Parses the base sample time from the C code
+///
+/// The sample time is given either by the period of `rt_OneStep` in the example main
+/// or by the `Sample time` comments of the blocks
+fn sample_time(code: &str) -> Option<f64> {
+    let regex =
+        Regex::new(r"period\s+([0-9.eE+-]+)\s+seconds|Sample time:\s*\[([0-9.eE+-]+)s").unwrap();
+    let sample_time = regex.captures_iter(code).find_map(|caps| {
+        caps.get(1)
+            .or(caps.get(2))
+            .and_then(|m| m.as_str().parse().ok())
+    });
+    sample_time
+}
+
+impl Model {
+    /// Parses the C code of the main header file into [Model]
+    ///
+    /// Extract the model name, the lists of inputs, outputs, states and parameters variables,
+    /// the C symbols and the metadata from the main header file
+    pub fn parse(code: &str) -> io::Result<Self> {
+        let mut lines = code.as_bytes().lines();
+
+        let regex = Regex::new(r"File:\s*(\w+)\.h").unwrap();
+        let mut name = None;
+        while let Some(Ok(line)) = lines.next() {
+            if let Some(captures) = regex.captures(&line) {
+                name = Some(captures.get(1).unwrap().as_str().to_string());
+                break;
+            }
+        }
+        let Some(name) = name else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "cannot find the model name",
+            ));
+        };
+        let mut simulink = vec![];
+        while let Some(data) = Simulink::parse_io(&mut lines) {
+            simulink.push(data);
+        }
+        let mut symbols = Symbols::default();
+        symbols.parse(code);
+        Ok(Model {
+            name,
+            simulink,
+            symbols,
+            metadata: Metadata::parse(code),
+            sample_time: None,
+        })
+    }
+    /// Parses the main header file read from `reader` into [Model]
+    ///
+    /// The sample time is not available from the main header file
+    pub fn from_reader<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let mut code = String::new();
+        reader.read_to_string(&mut code)?;
+        Self::parse(&code)
+    }
+    /// Parses the main header file into [Model]
+    ///
+    /// Extract the model name and the lists of inputs, outputs, states and parameters variables
+    /// from the main header file, the C symbols from the main and types header files
+    /// and the sample time from the source files
+    pub fn from_files(header: &Path, headers: &[PathBuf], sources: &[PathBuf]) -> io::Result<Self> {
+        let mut model = Self::parse(&fs::read_to_string(header)?)?;
+        let types_header = format!("{}_types.h", model.name);
+        for path in headers.iter().filter(|path| path.ends_with(&types_header)) {
+            model.symbols.parse(&fs::read_to_string(path)?);
+        }
+        for path in sources {
+            model.sample_time = self::sample_time(&fs::read_to_string(path)?);
+            if model.sample_time.is_some() {
+                break;
+            }
+        }
+        Ok(model)
+    }
+    /// Returns the Simulink structure which name starts with `prefix`
+    fn structure(&self, prefix: &str) -> Option<&Simulink> {
+        self.simulink
+            .iter()
+            .find(|simulink| simulink.name.starts_with(prefix))
+    }
+    /// Returns the inputs structure (`ExtU_<model>_T`)
+    pub fn inputs(&self) -> Option<&Simulink> {
+        self.structure("ExtU_")
+    }
+    /// Returns the outputs structure (`ExtY_<model>_T`)
+    pub fn outputs(&self) -> Option<&Simulink> {
+        self.structure("ExtY_")
+    }
+    /// Returns the states structure (`DW_<model>_T`)
+    pub fn states(&self) -> Option<&Simulink> {
+        self.structure("DW_")
+    }
+    /// Returns the parameters structure (`P_<model>_T`)
+    pub fn parameters(&self) -> Option<&Simulink> {
+        self.structure("P_")
+    }
+}
+
+impl FromStr for Model {
+    type Err = io::Error;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Self::parse(code)
+    }
+}
+
+/// Collects the C header and source files in `folder`, and in its sub-folders if `recursive` is set
+///
+/// Returns the sorted lists of header and source files
+pub fn c_files(folder: &Path, recursive: bool) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    fn discover(folder: &Path, recursive: bool, files: &mut (Vec<PathBuf>, Vec<PathBuf>)) {
+        let Ok(entries) = fs::read_dir(folder) else {
+            return;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.is_dir() {
+                if recursive {
+                    discover(&path, recursive, files);
+                }
+                continue;
+            }
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("h") => files.0.push(path),
+                Some("c") => files.1.push(path),
+                _ => (),
+            }
+        }
+    }
+    let mut files = Default::default();
+    discover(folder, recursive, &mut files);
+    files.0.sort();
+    files.1.sort();
+    files
+}
+
+/// Environment variables of the C files resolved by the build script, see [export_files]
+const SYS_FOLDER_ENV: &str = "SIMULINK_RS_SYS_FOLDER";
+const HEADERS_ENV: &str = "SIMULINK_RS_HEADERS";
+const SOURCES_ENV: &str = "SIMULINK_RS_SOURCES";
+
+/// Passes the C header and source files of `folder` resolved by the build script,
+/// e.g. with glob patterns, on to the compilation of the crate
+///
+/// The files are read back with [resolved_files]
+pub fn export_files(folder: &Path, headers: &[PathBuf], sources: &[PathBuf]) {
+    let join = |paths: &[PathBuf]| env::join_paths(paths).ok()?.into_string().ok();
+    let (Some(folder), Some(headers), Some(sources)) =
+        (folder.to_str(), join(headers), join(sources))
+    else {
+        println!("cargo:warning=the C file paths cannot be passed on to the crate compilation");
+        return;
+    };
+    println!("cargo:rustc-env={}={}", SYS_FOLDER_ENV, folder);
+    println!("cargo:rustc-env={}={}", HEADERS_ENV, headers);
+    println!("cargo:rustc-env={}={}", SOURCES_ENV, sources);
+}
+
+/// Returns the C header and source files of `folder` resolved by the build script
+///
+/// Returns `None` if the build script has not resolved the files of this folder
+pub fn resolved_files(folder: &Path) -> Option<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let sys = PathBuf::from(env::var_os(SYS_FOLDER_ENV)?);
+    if sys != folder && sys.canonicalize().ok()? != folder.canonicalize().ok()? {
+        return None;
+    }
+    let split = |var: &str| -> Option<Vec<PathBuf>> {
+        Some(
+            env::split_paths(&env::var_os(var)?)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect(),
+        )
+    };
+    Some((split(HEADERS_ENV)?, split(SOURCES_ENV)?))
+}
+
+/// Returns the main header file of a Simulink model
+///
+/// Headers with a source file of the same name come first
+pub fn main_header<'a>(headers: &'a [PathBuf], sources: &[PathBuf]) -> Option<&'a Path> {
+    let mut headers: Vec<_> = headers
+        .iter()
+        .filter(|header| {
+            header.to_str().is_some_and(|f| {
+                !(f.ends_with("rtwtypes.h")
+                    || f.ends_with("rt_defines.h")
+                    || f.ends_with("_private.h")
+                    || f.ends_with("_types.h"))
+            })
+        })
+        .collect();
+    headers.sort_by_key(|header| {
+        !sources
+            .iter()
+            .any(|source| &source.with_extension("h") == *header)
+    });
+    headers.first().map(|header| header.as_path())
+}
+
+/// C symbols declared in the Simulink model header files
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Symbols {
+    /// structure, enum and bus typedefs
+    pub types: Vec<String>,
+    /// entry point functions
+    pub functions: Vec<String>,
+    /// global variables
+    pub variables: Vec<String>,
+}
+impl Symbols {
+    /// Parses the C code of a header file for typedefs and extern declarations
+    pub fn parse(&mut self, code: &str) {
+        let re_comment = Regex::new(r"(?s)/\*.*?\*/|//[^\n]*").unwrap();
+        let code = re_comment.replace_all(code, "");
+        let code = code.as_ref();
+        let re_typedef =
+            Regex::new(r"(?s)typedef\s+(?:struct|enum)\s*(\w*)\s*\{[^{}]*\}\s*(\w+)\s*;").unwrap();
+        let re_forward = Regex::new(r"typedef\s+struct\s+(\w+)\s+(\w+)\s*;").unwrap();
+        let re_function = Regex::new(r"(?m)^extern\s+[\w\s\*]+?\b(\w+)\s*\(").unwrap();
+        let re_variable = Regex::new(r"(?m)^extern\s+(?:const\s+)?\w+\s+(\w+)\s*;").unwrap();
+        for caps in re_typedef
+            .captures_iter(code)
+            .chain(re_forward.captures_iter(code))
+        {
+            for name in [caps.get(1), caps.get(2)]
+                .into_iter()
+                .flatten()
+                .map(|m| m.as_str())
+                .filter(|name| !name.is_empty())
+            {
+                push_unique(&mut self.types, name);
+            }
+        }
+        for caps in re_function.captures_iter(code) {
+            push_unique(&mut self.functions, &caps[1]);
+        }
+        for caps in re_variable.captures_iter(code) {
+            push_unique(&mut self.variables, &caps[1]);
+        }
+    }
+}
+fn push_unique(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|n| n == name) {
+        names.push(name.to_string());
+    }
+}
+
+/// Rust wrapper code generation options
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Name of the Rust type alias of the controller
+    pub rs_type: Option<String>,
+    /// Extra derive macros of the controller, e.g. `PartialEq`
+    pub derives: Vec<syn::Path>,
+}
+
+impl Model {
+    /// Generates the Rust wrapper of the Simulink C controller
+    pub fn wrapper(&self, options: &Options) -> TokenStream {
+        let mut tokens = TokenStream::new();
+        if let Some(rs_type) = options.rs_type.as_ref() {
+            let rs_type = format_ident!("{}", rs_type);
+            let name = format_ident!("{}", self.name);
+            tokens.extend(quote! {
+                /// Rust binder to Simulink C controller wrapper
+                #[allow(dead_code)]
+                pub type #rs_type = #name;
+            });
+        }
+        let model = format_ident!("{}", self.name);
+        let ext_u = format_ident!("ExtU_{}_T", self.name);
+        let ext_y = format_ident!("ExtY_{}_T", self.name);
+        let dw = format_ident!("DW_{}_T", self.name);
+        let rt_model = format_ident!("RT_MODEL_{}_T", self.name);
+        let tag_rtm = format_ident!("tag_RTM_{}_T", self.name);
+        let initialize = format_ident!("{}_initialize", self.name);
+        let step = format_ident!("{}_step", self.name);
+        let defaults = self.simulink.iter().map(|simulink| simulink.default_impl());
+        let flatten = self.simulink.iter().map(|simulink| simulink.flatten_impl());
+        let interface_hash = self.interface_hash();
+        let wire = self
+            .simulink
+            .iter()
+            .map(|simulink| simulink.wire_impl(interface_hash));
+        let serde = &self.simulink;
+        let derives = &options.derives;
+        let info = self.info();
+        let accessors = self.accessors();
+        let registry = self.registry();
+        let flat = self.flat();
+        tokens.extend(quote! {
+            /// Simulink controller wrapper
+            #[derive(Clone, Copy, Debug, Default, ::serde::Serialize, ::serde::Deserialize #(, #derives)*)]
+            pub struct #model {
+                /// Inputs Simulink structure
+                pub inputs: #ext_u,
+                /// Outputs Simulink structure
+                pub outputs: #ext_y,
+                states: #dw,
+            }
+            #(#defaults)*
+            #(#flatten)*
+            #(#wire)*
+            impl #model {
+                /// Creates a new controller
+                pub fn new() -> Self {
+                    let mut this: Self = Default::default();
+                    let mut data: #rt_model = #tag_rtm {
+                        dwork: &mut this.states as *mut _,
+                    };
+                    unsafe { #initialize(&mut data as *mut _) }
+                    this
+                }
+                /// Steps the controller
+                pub fn step(&mut self) {
+                    let mut data: #rt_model = #tag_rtm {
+                        dwork: &mut self.states as *mut _,
+                    };
+                    unsafe {
+                        #step(
+                            &mut data as *mut _,
+                            &mut self.inputs as *mut _,
+                            &mut self.outputs as *mut _,
+                        )
+                    }
+                }
+            }
+            #accessors
+            #registry
+            #flat
+            impl ::simulink_rs::SimulinkController for #model {
+                type Inputs = #ext_u;
+                type Outputs = #ext_y;
+                type States = #dw;
+                fn new() -> Self {
+                    #model::new()
+                }
+                fn step(&mut self) {
+                    #model::step(self)
+                }
+                fn reset(&mut self) {
+                    *self = #model::new();
+                }
+                fn inputs(&self) -> &Self::Inputs {
+                    &self.inputs
+                }
+                fn inputs_mut(&mut self) -> &mut Self::Inputs {
+                    &mut self.inputs
+                }
+                fn outputs(&self) -> &Self::Outputs {
+                    &self.outputs
+                }
+                fn outputs_mut(&mut self) -> &mut Self::Outputs {
+                    &mut self.outputs
+                }
+                fn states(&self) -> &Self::States {
+                    &self.states
+                }
+                fn states_mut(&mut self) -> &mut Self::States {
+                    &mut self.states
+                }
+                fn signals(&self) -> &'static [::simulink_rs::SignalInfo] {
+                    #model::SIGNALS
+                }
+                fn get(&self, name: &str) -> Option<&[f64]> {
+                    #model::get(self, name)
+                }
+                fn set(&mut self, name: &str, value: &[f64]) -> Result<(), ::simulink_rs::SignalError> {
+                    #model::set(self, name, value)
+                }
+                fn run_while<B, A>(&mut self, mut before: B, mut after: A)
+                where
+                    B: FnMut(usize, &mut Self::Inputs) -> bool,
+                    A: FnMut(usize, &Self::Outputs, &Self::States),
+                {
+                    let mut data: #rt_model = #tag_rtm {
+                        dwork: &mut self.states as *mut _,
+                    };
+                    let mut i = 0;
+                    while before(i, &mut self.inputs) {
+                        unsafe {
+                            #step(
+                                &mut data as *mut _,
+                                &mut self.inputs as *mut _,
+                                &mut self.outputs as *mut _,
+                            )
+                        }
+                        after(i, &self.outputs, unsafe { &*data.dwork });
+                        i += 1;
+                    }
+                }
+                fn info() -> ::simulink_rs::ModelInfo {
+                    #info
+                }
+            }
+            #(#serde)*
+        });
+        tokens
+    }
+}
+
+impl Model {
+    /// Computes the 64 bits hash of the model interface
+    ///
+    /// The hash depends on the model name and on the names, types, sizes and order of the
+    /// inputs, outputs, states and parameters, but not on the model version nor on the block paths,
+    /// it is the same as the hash of the interface lockfile
+    pub fn interface_hash(&self) -> u64 {
+        fn ports(simulink: Option<&Simulink>) -> impl Iterator<Item = (&str, &str, Option<usize>)> {
+            simulink
+                .into_iter()
+                .flat_map(|simulink| simulink.properties.iter())
+                .map(|io| (io.name.as_str(), io.dtype.as_str(), io.size))
+        }
+        interface_hash(
+            &self.name,
+            [
+                ("inputs", ports(self.inputs())),
+                ("outputs", ports(self.outputs())),
+                ("states", ports(self.states())),
+                ("parameters", ports(self.parameters())),
+            ],
+        )
+    }
+    /// Returns the `simulink_rs::ModelInfo` of the model
+    fn info(&self) -> TokenStream {
+        let option = |value: &Option<String>| match value {
+            Some(value) => quote!(Some(#value)),
+            None => quote!(None),
+        };
+        let name = &self.name;
+        let model_version = option(&self.metadata.model_version);
+        let coder_version = option(&self.metadata.coder_version);
+        let generated_on = option(&self.metadata.generated_on);
+        let sample_time = match self.sample_time {
+            Some(sample_time) => {
+                let sample_time = Literal::f64_suffixed(sample_time);
+                quote!(Some(#sample_time))
+            }
+            None => quote!(None),
+        };
+        let interface_hash = Literal::u64_suffixed(self.interface_hash());
+        quote! {
+            ::simulink_rs::ModelInfo {
+                name: #name,
+                model_version: #model_version,
+                coder_version: #coder_version,
+                generated_on: #generated_on,
+                sample_time: #sample_time,
+                interface_hash: #interface_hash,
+            }
+        }
+    }
+}
+
+impl ToTokens for Model {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(self.wrapper(&Options::default()));
+    }
+}
+
+impl Display for Model {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", pretty(self.to_token_stream()))
+    }
+}
+
+/// Computes the 64 bits FNV-1a digest of some bytes
+pub fn fnv1a<'a>(bytes: impl IntoIterator<Item = &'a [u8]>) -> u64 {
+    bytes
+        .into_iter()
+        .flatten()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Computes the 64 bits hash of a model interface
+///
+/// The hash is the [fnv1a] digest of the model name followed by one line per port,
+/// e.g. `inputs[0] u: real_T[3]`, with the name, the type and the size of the ports
+/// of each group
+pub fn interface_hash<'a, G, P>(model: &str, groups: G) -> u64
+where
+    G: IntoIterator<Item = (&'a str, P)>,
+    P: IntoIterator<Item = (&'a str, &'a str, Option<usize>)>,
+{
+    let lines: Vec<_> = groups
+        .into_iter()
+        .flat_map(|(group, ports)| {
+            ports
+                .into_iter()
+                .enumerate()
+                .map(move |(i, (name, dtype, size))| match size {
+                    Some(size) => format!("{}[{}] {}: {}[{}]", group, i, name, dtype, size),
+                    None => format!("{}[{}] {}: {}", group, i, name, dtype),
+                })
+        })
+        .collect();
+    fnv1a(
+        std::iter::once(model.as_bytes()).chain(
+            lines
+                .iter()
+                .flat_map(|line| ["\n".as_bytes(), line.as_bytes()]),
+        ),
+    )
+}
+
+/// Pretty-prints Rust code
+pub fn pretty(tokens: TokenStream) -> String {
+    let file: syn::File = syn::parse2(tokens).expect("failed to parse the generated code");
+    prettyplease::unparse(&file)
+}
+
+/// Creates a Rust identifier from a C identifier
+///
+/// Rust keywords are given a trailing underscore, as bindgen does
+pub fn rust_ident(name: &str) -> Ident {
+    if syn::parse_str::<Ident>(name).is_ok() {
+        format_ident!("{}", name)
+    } else {
+        format_ident!("{}_", name)
+    }
+}
+
+impl Simulink {
+    /// Parse the Simulink C header file to extract inputs, outputs, states or parameters variables
+    ///
+    /// The constant parameters structure is skipped
+    pub fn parse_io<B: BufRead>(lines: &mut io::Lines<B>) -> Option<Self> {
+        let re_prop =
+            Regex::new(r"^\s*(?P<dtype>\w+)\s+(?P<name>\w+)(?:\[(?P<size>\d+)\])?\s*;").unwrap();
+        let re_struct = Regex::new(r"^\s*}\s*(\w+)\s*;").unwrap();
+        let re_params = Regex::new(r"^struct\s+(P_\w+?)_?\s*\{").unwrap();
+        let re_block = Regex::new(r"'(<[^']*>[^']*)'").unwrap();
+        'header: loop {
+            let line = match lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(_)) => continue 'header,
+                None => break 'header None,
+            };
+            // parameters are declared as `struct P_<model>_T_ { ... };`
+            let tag = if line.starts_with("typedef struct") {
+                None
+            } else if let Some(caps) = re_params.captures(&line) {
+                Some(caps[1].to_string())
+            } else {
+                continue 'header;
+            };
+            let mut this = Self::default();
+            while let Some(Ok(line)) = lines.next() {
+                let name = match (&tag, re_struct.captures(&line)) {
+                    (None, Some(caps)) => caps[1].to_string(),
+                    (Some(tag), _) if line.trim_start().starts_with("};") => tag.clone(),
+                    _ => {
+                        // the block path is in the comment following the property,
+                        // e.g. `/* '<Root>/u' */` or `* Referenced by: '<Root>/Gain'`
+                        let block_path = re_block.captures(&line).map(|caps| caps[1].to_string());
+                        if let Some(caps) = re_prop.captures(&line) {
+                            let size = caps.name("size").map(|m| m.as_str());
+                            let mut io = IO::new(&caps["dtype"], &caps["name"], size);
+                            io.block_path = block_path;
+                            this.properties.push(io);
+                        } else if let Some(io) = this
+                            .properties
+                            .last_mut()
+                            .filter(|io| io.block_path.is_none())
+                        {
+                            io.block_path = block_path;
+                        }
+                        continue;
+                    }
+                };
+                if name.starts_with("ConstP") {
+                    continue 'header;
+                }
+                this.name = name;
+                break 'header Some(this);
+            }
+        }
+    }
+    /// Implements [Default] for the Simulink structure
+    fn default_impl(&self) -> TokenStream {
+        let name = format_ident!("{}", self.name);
+        let properties = self.properties.iter().map(|IO { name, size, .. }| {
+            let name = rust_ident(name);
+            if let Some(size) = size {
+                let size = Literal::usize_unsuffixed(*size);
+                quote!(#name: [Default::default(); #size])
+            } else {
+                quote!(#name: Default::default())
+            }
+        });
+        quote! {
+            impl Default for #name {
+                fn default() -> Self {
+                    Self { #(#properties),* }
+                }
+            }
+        }
+    }
+}
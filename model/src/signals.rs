@@ -0,0 +1,586 @@
+//! Signal accessors of the Rust wrapper
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::{rust_ident, Model, Simulink, IO};
+
+/// Methods of the controller that the accessors must not shadow
+const RESERVED: [&str; 30] = [
+    // methods of the wrapper
+    "new",
+    "step",
+    "signals",
+    "get",
+    "set",
+    "inputs_from_slice",
+    "outputs_into",
+    "outputs_to_vec",
+    // methods of `simulink_rs::SimulinkController`
+    "reset",
+    "inputs",
+    "inputs_mut",
+    "outputs",
+    "outputs_mut",
+    "states",
+    "states_mut",
+    "run",
+    "run_while",
+    "sample_time",
+    "info",
+    "snapshot",
+    "snapshot_with_io",
+    "restore",
+    // methods of the derived traits
+    "clone",
+    "clone_from",
+    "default",
+    "fmt",
+    "serialize",
+    "deserialize",
+    "eq",
+    "ne",
+];
+
+/// C data types of the numeric signals and their Rust types
+const DTYPES: [(&str, &str); 16] = [
+    ("real_T", "f64"),
+    ("real64_T", "f64"),
+    ("double", "f64"),
+    ("real32_T", "f32"),
+    ("float", "f32"),
+    ("int8_T", "i8"),
+    ("uint8_T", "u8"),
+    ("int16_T", "i16"),
+    ("uint16_T", "u16"),
+    ("int32_T", "i32"),
+    ("uint32_T", "u32"),
+    ("int64_T", "i64"),
+    ("uint64_T", "u64"),
+    ("boolean_T", "u8"),
+    ("int", "i32"),
+    // `char` is signed or unsigned depending on the target
+    ("char_T", "::core::ffi::c_char"),
+];
+
+/// Returns the Rust type of a C data type
+///
+/// The types that are not numeric, e.g. the structures of the bindings, keep their names
+fn rust_type(dtype: &str) -> TokenStream {
+    match DTYPES.iter().find(|(c, _)| *c == dtype) {
+        Some((_, rust)) => rust.parse().unwrap(),
+        None => {
+            let dtype = format_ident!("{}", dtype);
+            quote!(#dtype)
+        }
+    }
+}
+
+/// Checks if a C data type is a numeric type
+fn is_numeric(dtype: &str) -> bool {
+    DTYPES.iter().any(|(c, _)| *c == dtype)
+}
+
+/// Checks if a C data type is a `f64` type
+fn is_f64(dtype: &str) -> bool {
+    DTYPES.contains(&(dtype, "f64"))
+}
+
+/// Converts a Simulink signal name into snake case, e.g. `SA_offsetF_cmd` into `sa_offset_f_cmd`
+pub fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for word in name.split('_').filter(|word| !word.is_empty()) {
+        if !snake.is_empty() {
+            snake.push('_');
+        }
+        let chars: Vec<char> = word.chars().collect();
+        for (i, c) in chars.iter().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                let previous = chars[i - 1];
+                let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+                if previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase() && next_is_lower)
+                {
+                    snake.push('_');
+                }
+            }
+            snake.extend(c.to_lowercase());
+        }
+    }
+    // Rust keywords, e.g. `type` or `self`, and the methods of the controller are suffixed
+    if RESERVED.contains(&snake.as_str()) || syn::parse_str::<syn::Ident>(&snake).is_err() {
+        snake.push('_');
+    }
+    snake
+}
+
+/// Snake case names of the inputs and outputs accessors
+///
+/// The names are unique: a name shared by an input and an output is prefixed with `input_`
+/// or `output_`, e.g. `input_u` and `output_u` for the input `u` and the output `U`,
+/// and a name still shared by two inputs or two outputs is suffixed with a rank, e.g. `u_2`
+struct SnakeNames(Vec<(bool, String, String)>);
+impl SnakeNames {
+    /// Returns the snake case name of the accessor of an input or of an output
+    fn get(&self, is_input: bool, name: &str) -> &str {
+        self.0
+            .iter()
+            .find(|(input, signal, _)| *input == is_input && signal == name)
+            .map(|(_, _, snake)| snake.as_str())
+            .unwrap_or_default()
+    }
+}
+
+impl Model {
+    /// Returns the snake case names of the accessors of the inputs and outputs
+    fn snake_names(&self) -> SnakeNames {
+        let signals: Vec<(bool, &str, String)> = [(true, self.inputs()), (false, self.outputs())]
+            .into_iter()
+            .flat_map(|(is_input, simulink)| {
+                simulink
+                    .into_iter()
+                    .flat_map(|simulink| simulink.properties.iter())
+                    .map(move |io| (is_input, io.name.as_str(), snake_case(&io.name)))
+            })
+            .collect();
+        let mut names: Vec<(bool, String, String)> = vec![];
+        for (is_input, name, snake) in &signals {
+            let mut snake = if signals
+                .iter()
+                .any(|(input, _, other)| input != is_input && other == snake)
+            {
+                let prefix = if *is_input { "input" } else { "output" };
+                format!("{}_{}", prefix, snake.trim_end_matches('_'))
+            } else {
+                snake.clone()
+            };
+            let base = snake.trim_end_matches('_').to_string();
+            let mut rank = 1;
+            while names.iter().any(|(_, _, other)| *other == snake) {
+                rank += 1;
+                snake = format!("{}_{}", base, rank);
+            }
+            names.push((*is_input, name.to_string(), snake));
+        }
+        SnakeNames(names)
+    }
+    /// Generates the snake case accessors of the inputs and outputs
+    pub(crate) fn accessors(&self) -> TokenStream {
+        let model = format_ident!("{}", self.name);
+        let snake_names = self.snake_names();
+        let getters = |field: TokenStream, io: &IO, kind: &str| {
+            let IO {
+                name, dtype, size, ..
+            } = io;
+            let ident = rust_ident(name);
+            let snake = snake_names.get(kind == "input", name);
+            let getter = format_ident!("{}", snake);
+            let dtype = rust_type(dtype);
+            let doc = format!("Returns the `{}` {}{}", name, kind, block_path(io));
+            // an alias cannot be the name of the accessor
+            let alias = (*name != snake).then(|| quote!(#[doc(alias = #name)]));
+            let value = match size {
+                Some(_) => quote!(&self.#field.#ident),
+                None => quote!(::std::slice::from_ref(&self.#field.#ident)),
+            };
+            quote! {
+                #[doc = #doc]
+                #alias
+                pub fn #getter(&self) -> &[#dtype] {
+                    #value
+                }
+            }
+        };
+        let inputs = self.inputs().into_iter().flat_map(|simulink| {
+            simulink.properties.iter().map(|io| {
+                let IO { name, dtype, size, .. } = io;
+                let getter = getters(quote!(inputs), io, "input");
+                let ident = rust_ident(name);
+                let setter = format_ident!("set_{}", snake_names.get(true, name).trim_end_matches('_'));
+                let dtype = rust_type(dtype);
+                let doc = format!("Sets the `{}` input{}", name, block_path(io));
+                let expected = size.unwrap_or(1);
+                let assign = match size {
+                    Some(_) => quote!(self.inputs.#ident.copy_from_slice(value)),
+                    None => quote!(self.inputs.#ident = value[0]),
+                };
+                quote! {
+                    #getter
+                    #[doc = #doc]
+                    ///
+                    /// Returns an error if the length of `value` is not the size of the input
+                    #[doc(alias = #name)]
+                    pub fn #setter(&mut self, value: &[#dtype]) -> Result<(), ::simulink_rs::SignalError> {
+                        if value.len() != #expected {
+                            return Err(::simulink_rs::SignalError::Length {
+                                signal: #name,
+                                expected: #expected,
+                                found: value.len(),
+                            });
+                        }
+                        #assign;
+                        Ok(())
+                    }
+                }
+            })
+        });
+        let outputs = self.outputs().into_iter().flat_map(|simulink| {
+            simulink
+                .properties
+                .iter()
+                .map(|io| getters(quote!(outputs), io, "output"))
+        });
+        quote! {
+            impl #model {
+                #(#inputs)*
+                #(#outputs)*
+            }
+        }
+    }
+}
+
+impl Model {
+    /// Generates the signal table and the access to the signals by name
+    pub(crate) fn registry(&self) -> TokenStream {
+        let model = format_ident!("{}", self.name);
+        let snake_names = self.snake_names();
+        // names already matched by `get` and by `set`, the first signal of a given name wins
+        let mut get_names: Vec<String> = vec![];
+        let mut set_names: Vec<String> = vec![];
+        let mut signals = vec![];
+        let mut getters = vec![];
+        let mut setters = vec![];
+        for (simulink, structure, field, is_input) in [
+            (
+                self.inputs(),
+                format_ident!("ExtU_{}_T", self.name),
+                quote!(inputs),
+                true,
+            ),
+            (
+                self.outputs(),
+                format_ident!("ExtY_{}_T", self.name),
+                quote!(outputs),
+                false,
+            ),
+        ] {
+            let direction = if is_input {
+                quote!(Input)
+            } else {
+                quote!(Output)
+            };
+            for io in simulink
+                .into_iter()
+                .flat_map(|simulink| simulink.properties.iter())
+            {
+                let IO {
+                    name,
+                    dtype,
+                    size,
+                    block_path,
+                } = io;
+                let ident = rust_ident(name);
+                let block_path = match block_path {
+                    Some(block_path) => quote!(Some(#block_path)),
+                    None => quote!(None),
+                };
+                let len = size.unwrap_or(1);
+                signals.push(quote! {
+                    ::simulink_rs::SignalInfo {
+                        name: #name,
+                        direction: ::simulink_rs::Direction::#direction,
+                        dtype: #dtype,
+                        size: #len,
+                        offset: ::core::mem::offset_of!(#structure, #ident),
+                        block_path: #block_path,
+                    }
+                });
+
+                let snake = snake_names.get(is_input, name);
+                let is_f64 = is_f64(dtype);
+                if let Some(pattern) = is_f64
+                    .then(|| pattern(&mut get_names, name, snake))
+                    .flatten()
+                {
+                    let value = match size {
+                        Some(_) => quote!(&self.#field.#ident[..]),
+                        None => quote!(::std::slice::from_ref(&self.#field.#ident)),
+                    };
+                    getters.push(quote!(#pattern => Some(#value),));
+                }
+                if let Some(pattern) = is_input
+                    .then(|| pattern(&mut set_names, name, snake))
+                    .flatten()
+                {
+                    let setter = format_ident!("set_{}", snake.trim_end_matches('_'));
+                    setters.push(if is_f64 {
+                        quote!(#pattern => self.#setter(value),)
+                    } else {
+                        quote! {
+                            #pattern => Err(::simulink_rs::SignalError::Dtype {
+                                signal: #name,
+                                dtype: #dtype,
+                            }),
+                        }
+                    });
+                }
+            }
+        }
+        quote! {
+            impl #model {
+                /// Inputs and outputs signals of the controller
+                pub const SIGNALS: &'static [::simulink_rs::SignalInfo] = &[#(#signals),*];
+                /// Returns the inputs and outputs signals of the controller
+                pub fn signals(&self) -> &'static [::simulink_rs::SignalInfo] {
+                    Self::SIGNALS
+                }
+                /// Returns the values of an input or output signal given its Simulink or snake case name
+                ///
+                /// Returns `None` if the signal does not exist or is not a `f64` signal
+                pub fn get(&self, name: &str) -> Option<&[f64]> {
+                    match name {
+                        #(#getters)*
+                        _ => None,
+                    }
+                }
+                /// Sets the values of an input signal given its Simulink or snake case name
+                pub fn set(&mut self, name: &str, value: &[f64]) -> Result<(), ::simulink_rs::SignalError> {
+                    match name {
+                        #(#setters)*
+                        _ => Err(::simulink_rs::SignalError::NotFound(name.to_string())),
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Simulink {
+    /// Returns the properties of a numeric type, that are flattened into `f64` slices
+    fn numeric(&self) -> impl Iterator<Item = &IO> {
+        self.properties.iter().filter(|io| is_numeric(&io.dtype))
+    }
+    /// Implements `simulink_rs::Flatten` for the Simulink structure
+    ///
+    /// The properties are flattened in order, the properties that are not of a numeric type
+    /// are left out
+    pub(crate) fn flatten_impl(&self) -> TokenStream {
+        let structure = format_ident!("{}", self.name);
+        let name = &self.name;
+        let mut offset = 0usize;
+        let mut from = vec![];
+        let mut to = vec![];
+        for IO {
+            name, dtype, size, ..
+        } in self.numeric()
+        {
+            let ident = rust_ident(name);
+            let len = size.unwrap_or(1);
+            let (start, end) = (offset, offset + len);
+            offset = end;
+            let is_f64 = is_f64(dtype);
+            let dtype = rust_type(dtype);
+            match (size.is_some(), is_f64) {
+                (true, true) => {
+                    from.push(quote!(self.#ident.copy_from_slice(&data[#start..#end]);));
+                    to.push(quote!(data[#start..#end].copy_from_slice(&self.#ident);));
+                }
+                (true, false) => {
+                    from.push(quote! {
+                        for (x, value) in self.#ident.iter_mut().zip(&data[#start..#end]) {
+                            *x = *value as #dtype;
+                        }
+                    });
+                    to.push(quote! {
+                        for (value, x) in data[#start..#end].iter_mut().zip(&self.#ident) {
+                            *value = *x as f64;
+                        }
+                    });
+                }
+                (false, true) => {
+                    from.push(quote!(self.#ident = data[#start];));
+                    to.push(quote!(data[#start] = self.#ident;));
+                }
+                (false, false) => {
+                    from.push(quote!(self.#ident = data[#start] as #dtype;));
+                    to.push(quote!(data[#start] = self.#ident as f64;));
+                }
+            }
+        }
+        let check = quote! {
+            if data.len() != #offset {
+                return Err(::simulink_rs::SignalError::Length {
+                    signal: #name,
+                    expected: #offset,
+                    found: data.len(),
+                });
+            }
+        };
+        quote! {
+            impl ::simulink_rs::Flatten for #structure {
+                const LEN: usize = #offset;
+                fn copy_from_slice(&mut self, data: &[f64]) -> Result<(), ::simulink_rs::SignalError> {
+                    #check
+                    #(#from)*
+                    Ok(())
+                }
+                fn copy_to_slice(&self, data: &mut [f64]) -> Result<(), ::simulink_rs::SignalError> {
+                    #check
+                    #(#to)*
+                    Ok(())
+                }
+            }
+        }
+    }
+    /// Implements `simulink_rs::Wire` for the Simulink structure
+    ///
+    /// The properties of a numeric type are encoded in order, as little-endian values,
+    /// the other properties are left out
+    pub(crate) fn wire_impl(&self, interface_hash: u64) -> TokenStream {
+        let structure = format_ident!("{}", self.name);
+        let mut lens = vec![];
+        let mut encode = vec![];
+        let mut decode = vec![];
+        for IO {
+            name, dtype, size, ..
+        } in self.numeric()
+        {
+            let ident = rust_ident(name);
+            let len = size.unwrap_or(1);
+            let dtype = rust_type(dtype);
+            lens.push(quote!(::core::mem::size_of::<#dtype>() * #len));
+            let (values, values_mut) = if size.is_some() {
+                (quote!(self.#ident.iter()), quote!(self.#ident.iter_mut()))
+            } else {
+                (
+                    quote!(::core::iter::once(&self.#ident)),
+                    quote!(::core::iter::once(&mut self.#ident)),
+                )
+            };
+            encode.push(quote! {
+                for value in #values {
+                    let end = offset + ::core::mem::size_of::<#dtype>();
+                    data[offset..end].copy_from_slice(&value.to_le_bytes());
+                    offset = end;
+                }
+            });
+            decode.push(quote! {
+                for value in #values_mut {
+                    let end = offset + ::core::mem::size_of::<#dtype>();
+                    *value = <#dtype>::from_le_bytes(data[offset..end].try_into().unwrap());
+                    offset = end;
+                }
+            });
+        }
+        let offset = (!lens.is_empty()).then(|| quote!(let mut offset = 0usize;));
+        let check = quote! {
+            if data.len() != Self::WIRE_LEN {
+                return Err(::simulink_rs::WireError::Length {
+                    expected: Self::WIRE_LEN,
+                    found: data.len(),
+                });
+            }
+        };
+        quote! {
+            impl ::simulink_rs::Wire for #structure {
+                const WIRE_LEN: usize = 0 #(+ #lens)*;
+                const INTERFACE_HASH: u64 = #interface_hash;
+                fn encode(&self, data: &mut [u8]) -> Result<(), ::simulink_rs::WireError> {
+                    #check
+                    #offset
+                    #(#encode)*
+                    Ok(())
+                }
+                fn decode(&mut self, data: &[u8]) -> Result<(), ::simulink_rs::WireError> {
+                    #check
+                    #offset
+                    #(#decode)*
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+impl Model {
+    /// Generates the conversions of the inputs and outputs from and to contiguous `f64` slices
+    ///
+    /// The signals are flattened in port order, the signals that are not of a numeric type
+    /// are left out
+    pub(crate) fn flat(&self) -> TokenStream {
+        let model = format_ident!("{}", self.name);
+        let ext_u = format_ident!("ExtU_{}_T", self.name);
+        let ext_y = format_ident!("ExtY_{}_T", self.name);
+        let snake_names = self.snake_names();
+        let mut offsets = vec![];
+        for (simulink, kind) in [(self.inputs(), "input"), (self.outputs(), "output")] {
+            let mut offset = 0usize;
+            for io in simulink.into_iter().flat_map(|simulink| simulink.numeric()) {
+                let offset_name = format_ident!(
+                    "{}_OFFSET",
+                    snake_names
+                        .get(kind == "input", &io.name)
+                        .trim_end_matches('_')
+                        .to_uppercase()
+                );
+                let doc = format!(
+                    "Offset of the `{}` {} in the flattened {}s",
+                    io.name, kind, kind
+                );
+                offsets.push(quote! {
+                    #[doc = #doc]
+                    pub const #offset_name: usize = #offset;
+                });
+                offset += io.size.unwrap_or(1);
+            }
+        }
+        quote! {
+            impl #model {
+                /// Length of the flattened inputs
+                pub const INPUTS_LEN: usize = <#ext_u as ::simulink_rs::Flatten>::LEN;
+                /// Length of the flattened outputs
+                pub const OUTPUTS_LEN: usize = <#ext_y as ::simulink_rs::Flatten>::LEN;
+                #(#offsets)*
+                /// Sets all the inputs from a contiguous slice, in port order
+                ///
+                /// Returns an error if the length of `data` is not [Self::INPUTS_LEN]
+                pub fn inputs_from_slice(&mut self, data: &[f64]) -> Result<(), ::simulink_rs::SignalError> {
+                    ::simulink_rs::Flatten::copy_from_slice(&mut self.inputs, data)
+                }
+                /// Copies all the outputs into a contiguous slice, in port order
+                ///
+                /// Returns an error if the length of `data` is not [Self::OUTPUTS_LEN]
+                pub fn outputs_into(&self, data: &mut [f64]) -> Result<(), ::simulink_rs::SignalError> {
+                    ::simulink_rs::Flatten::copy_to_slice(&self.outputs, data)
+                }
+                /// Returns all the outputs in a vector, in port order
+                pub fn outputs_to_vec(&self) -> Vec<f64> {
+                    ::simulink_rs::Flatten::to_vec(&self.outputs)
+                }
+            }
+        }
+    }
+}
+
+/// Returns the pattern that matches the Simulink or snake case name of a signal
+///
+/// The names already matched are left out, `None` is returned if both are
+fn pattern(matched: &mut Vec<String>, name: &str, snake: &str) -> Option<TokenStream> {
+    let mut names = vec![];
+    for name in [name, snake] {
+        if !matched.iter().any(|matched| matched == name) {
+            matched.push(name.to_string());
+            names.push(name);
+        }
+    }
+    (!names.is_empty()).then(|| quote!(#(#names)|*))
+}
+
+/// Returns the block path of a signal for the documentation
+fn block_path(io: &IO) -> String {
+    io.block_path
+        .as_ref()
+        .map(|block_path| format!(" (`{}`)", block_path))
+        .unwrap_or_default()
+}
@@ -1,17 +1,23 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
 use std::{
     fmt::Display,
     ops::{Deref, DerefMut},
 };
 
 /// Simulink structure properties
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct IO {
-    /// i/o variable name
+    /// i/o variable type
     pub dtype: String,
     /// i/o variable name
     pub name: String,
     /// i/o variable size
     pub size: Option<usize>,
+    /// Simulink block path, e.g. `<Root>/u`
+    pub block_path: Option<String>,
 }
 impl IO {
     /// Creates a new property
@@ -20,11 +26,13 @@ impl IO {
             dtype: dtype.to_string(),
             name: name.to_string(),
             size: size.and_then(|s| s.parse().ok()),
+            block_path: None,
         }
     }
 }
 /// List of Simulink properties
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct List(Vec<IO>);
 impl Deref for List {
     type Target = Vec<IO>;
@@ -33,47 +41,44 @@ impl Deref for List {
         &self.0
     }
 }
+impl From<Vec<IO>> for List {
+    fn from(properties: Vec<IO>) -> Self {
+        Self(properties)
+    }
+}
 impl DerefMut for List {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.0
     }
 }
-impl Display for List {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let var: Vec<_> = self
-            .0
-            .iter()
-            .map(|IO { name, size, .. }| {
-                if let Some(size) = size {
-                    format!("{}: [Default::default(); {}]", name, size)
-                } else {
-                    format!("{}: Default::default()", name)
-                }
-            })
-            .collect();
-        writeln!(f, "\n{}", var.join(",\n"))
-    }
-}
-
 /// Simulink structure
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Simulink {
+    /// C structure name
     pub name: String,
+    /// C structure fields
     pub properties: List,
 }
 pub trait SerializeImpl {
-    fn serialize_impl(&self) -> String;
+    fn serialize_impl(&self) -> TokenStream;
 }
 pub trait DeserializeImpl {
-    fn deserialize_impl(&self) -> String;
+    fn deserialize_impl(&self) -> TokenStream;
 }
 
 mod deserialize;
 mod serialize;
 
+impl ToTokens for Simulink {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(self.serialize_impl());
+        tokens.extend(self.deserialize_impl());
+    }
+}
+
 impl Display for Simulink {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", self.serialize_impl())?;
-        writeln!(f, "{}", self.deserialize_impl())
+        write!(f, "{}", crate::pretty(self.to_token_stream()))
     }
 }
@@ -0,0 +1,200 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use super::{DeserializeImpl, List, Simulink, IO};
+use crate::rust_ident;
+
+impl DeserializeImpl for IO {
+    fn deserialize_impl(&self) -> TokenStream {
+        let field = &self.name;
+        let ident = rust_ident(&self.name);
+        quote!(#field => Ok(Field::#ident))
+    }
+}
+
+impl DeserializeImpl for List {
+    fn deserialize_impl(&self) -> TokenStream {
+        let fields = self.iter().map(|field| field.deserialize_impl());
+        quote!(#(#fields,)*)
+    }
+}
+
+pub trait Visitor {
+    fn visit_map(&self) -> TokenStream;
+    fn visit_seq(&self) -> TokenStream;
+}
+impl Visitor for List {
+    fn visit_seq(&self) -> TokenStream {
+        let visit = self.iter().enumerate().map(|(i, field)| {
+            let ident = rust_ident(&field.name);
+            let index = Literal::usize_unsuffixed(i);
+            let next = if field.size.is_some() {
+                quote!(seq.next_element_seed(::simulink_rs::ArraySeed(&mut self.simulink.#ident))?)
+            } else {
+                quote!(seq.next_element()?.map(|value| self.simulink.#ident = value))
+            };
+            quote! {
+                if #next.is_none() {
+                    return if self.partial {
+                        Ok(())
+                    } else {
+                        Err(::serde::de::Error::invalid_length(#index, &self))
+                    };
+                }
+            }
+        });
+        quote!(#(#visit)*)
+    }
+    fn visit_map(&self) -> TokenStream {
+        let n = Literal::usize_unsuffixed(self.len());
+        let visit = self.iter().enumerate().map(|(i, field)| {
+            let name = &field.name;
+            let ident = rust_ident(&field.name);
+            let index = Literal::usize_unsuffixed(i);
+            let value = if field.size.is_some() {
+                quote!(map.next_value_seed(::simulink_rs::ArraySeed(&mut self.simulink.#ident))?;)
+            } else {
+                quote!(self.simulink.#ident = map.next_value()?;)
+            };
+            quote! {
+                Field::#ident => {
+                    if seen[#index] {
+                        return Err(::serde::de::Error::duplicate_field(#name));
+                    }
+                    #value
+                    seen[#index] = true;
+                }
+            }
+        });
+        quote! {
+            let mut seen = [false; #n];
+            while let Some(key) = map.next_key::<Field>()? {
+                match key {
+                    #(#visit)*
+                }
+            }
+            if !self.partial {
+                if let Some(i) = seen.iter().position(|seen| !seen) {
+                    return Err(::serde::de::Error::missing_field(FIELDS[i]));
+                }
+            }
+        }
+    }
+}
+
+impl DeserializeImpl for Simulink {
+    fn deserialize_impl(&self) -> TokenStream {
+        let sim = format_ident!("{}", self.name);
+        let name = &self.name;
+        let expecting = format!("struct {}", self.name);
+        let fields: Vec<_> = self
+            .properties
+            .iter()
+            .map(|prop| rust_ident(&prop.name))
+            .collect();
+        let fields_str = self.properties.iter().map(|prop| &prop.name);
+        let fields_match = self.properties.deserialize_impl();
+        let seq_visitor = self.properties.visit_seq();
+        let map_visitor = self.properties.visit_map();
+        quote! {
+            const _: () = {
+                const FIELDS: &[&str] = &[#(#fields_str),*];
+
+                enum Field {
+                    #(#fields),*
+                }
+
+                impl<'de> ::serde::de::Deserialize<'de> for Field {
+                    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                    where
+                        D: ::serde::de::Deserializer<'de>,
+                    {
+                        struct FieldVisitor;
+                        impl<'de> ::serde::de::Visitor<'de> for FieldVisitor {
+                            type Value = Field;
+                            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                                formatter.write_str("field identifier")
+                            }
+
+                            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                            where
+                                E: ::serde::de::Error,
+                            {
+                                match value {
+                                    #fields_match
+                                    _ => Err(::serde::de::Error::unknown_field(value, FIELDS)),
+                                }
+                            }
+                        }
+                        deserializer.deserialize_identifier(FieldVisitor)
+                    }
+                }
+
+                /// Sets the fields of the structure in place,
+                /// the missing fields keep their values if `partial` is `true`
+                struct SimulinkVisitor<'a> {
+                    simulink: &'a mut #sim,
+                    partial: bool,
+                }
+                impl<'de, 'a> ::serde::de::Visitor<'de> for SimulinkVisitor<'a> {
+                    type Value = ();
+                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+                    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+                    where
+                        V: ::serde::de::SeqAccess<'de>,
+                    {
+                        #seq_visitor
+                        Ok(())
+                    }
+                    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                    where
+                        A: ::serde::de::MapAccess<'de>,
+                    {
+                        #map_visitor
+                        Ok(())
+                    }
+                }
+
+                impl<'de> ::serde::de::Deserialize<'de> for #sim {
+                    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                    where
+                        D: ::serde::de::Deserializer<'de>,
+                    {
+                        let mut simulink = <#sim as Default>::default();
+                        deserializer.deserialize_struct(
+                            #name,
+                            FIELDS,
+                            SimulinkVisitor {
+                                simulink: &mut simulink,
+                                partial: false,
+                            },
+                        )?;
+                        Ok(simulink)
+                    }
+                }
+
+                impl ::simulink_rs::Patch for #sim {
+                    fn apply_patch<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+                    where
+                        D: ::serde::de::Deserializer<'de>,
+                    {
+                        // the patch is applied to a copy, so that the structure is unchanged on error
+                        let mut patched = *self;
+                        deserializer.deserialize_struct(
+                            #name,
+                            FIELDS,
+                            SimulinkVisitor {
+                                simulink: &mut patched,
+                                partial: true,
+                            },
+                        )?;
+                        *self = patched;
+                        Ok(())
+                    }
+                }
+            };
+        }
+    }
+}
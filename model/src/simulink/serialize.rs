@@ -0,0 +1,56 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{format_ident, quote};
+
+use super::{List, SerializeImpl, Simulink, IO};
+use crate::rust_ident;
+
+impl SerializeImpl for IO {
+    fn serialize_impl(&self) -> TokenStream {
+        let field = &self.name;
+        let ident = rust_ident(&self.name);
+        if self.size.is_some() {
+            quote! {
+                ::serde::ser::SerializeStruct::serialize_field(
+                    &mut serde_state,
+                    #field,
+                    &self.#ident[..],
+                )?;
+            }
+        } else {
+            quote! {
+                ::serde::ser::SerializeStruct::serialize_field(
+                    &mut serde_state,
+                    #field,
+                    &self.#ident,
+                )?;
+            }
+        }
+    }
+}
+
+impl SerializeImpl for List {
+    fn serialize_impl(&self) -> TokenStream {
+        self.iter().map(|io| io.serialize_impl()).collect()
+    }
+}
+
+impl SerializeImpl for Simulink {
+    fn serialize_impl(&self) -> TokenStream {
+        let sim = format_ident!("{}", self.name);
+        let name = &self.name;
+        let fields = self.properties.serialize_impl();
+        let n_field = Literal::usize_unsuffixed(self.properties.len());
+        quote! {
+            impl ::serde::ser::Serialize for #sim {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ::serde::ser::Serializer,
+                {
+                    let mut serde_state = serializer.serialize_struct(#name, #n_field)?;
+                    #fields
+                    ::serde::ser::SerializeStruct::end(serde_state)
+                }
+            }
+        }
+    }
+}
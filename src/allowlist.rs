@@ -0,0 +1,90 @@
+use simulink_model::Model;
+
+/// Bindgen allowlist and blocklist patterns
+///
+/// The symbols of the Simulink model are always allowlisted, the patterns are added to them
+#[derive(Debug, Default, Clone)]
+pub struct Allowlist {
+    pub(crate) types: Vec<String>,
+    pub(crate) functions: Vec<String>,
+    pub(crate) variables: Vec<String>,
+    pub(crate) blocked_types: Vec<String>,
+    pub(crate) blocked_functions: Vec<String>,
+}
+impl Allowlist {
+    /// Returns the types, functions and variables to allowlist for a model
+    pub(crate) fn resolve(&self, model: &Model) -> Allowlist {
+        let types = model
+            .simulink
+            .iter()
+            .map(|simulink| simulink.name.clone())
+            .chain(model.symbols.types.iter().cloned())
+            .chain([
+                format!("RT_MODEL_{}_T", model.name),
+                format!("tag_RTM_{}_T", model.name),
+            ])
+            .chain(self.types.iter().cloned());
+        let functions = model
+            .symbols
+            .functions
+            .iter()
+            .chain(&self.functions)
+            .cloned();
+        let variables = model
+            .symbols
+            .variables
+            .iter()
+            .chain(&self.variables)
+            .cloned();
+        Allowlist {
+            types: unique(types),
+            functions: unique(functions),
+            variables: unique(variables),
+            blocked_types: self.blocked_types.clone(),
+            blocked_functions: self.blocked_functions.clone(),
+        }
+    }
+    /// Returns the allowlisted types
+    pub fn types(&self) -> &[String] {
+        &self.types
+    }
+    /// Returns the allowlisted functions
+    pub fn functions(&self) -> &[String] {
+        &self.functions
+    }
+    /// Returns the allowlisted variables
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+    /// Applies the allowlist and the blocklist to the bindgen builder
+    #[cfg(feature = "bindgen")]
+    pub(crate) fn apply(&self, mut builder: bindgen::Builder) -> bindgen::Builder {
+        for item in &self.types {
+            builder = builder.allowlist_type(item);
+        }
+        for item in &self.functions {
+            builder = builder.allowlist_function(item);
+        }
+        for item in &self.variables {
+            builder = builder.allowlist_var(item);
+        }
+        for item in &self.blocked_types {
+            builder = builder.blocklist_type(item);
+        }
+        for item in &self.blocked_functions {
+            builder = builder.blocklist_function(item);
+        }
+        builder
+    }
+}
+
+/// Collects the items, leaving out duplicates
+fn unique(items: impl Iterator<Item = String>) -> Vec<String> {
+    let mut list: Vec<String> = vec![];
+    for item in items {
+        if !list.contains(&item) {
+            list.push(item);
+        }
+    }
+    list
+}
@@ -0,0 +1,45 @@
+use serde_json::json;
+use simulink_rs::Interface;
+use std::path::Path;
+
+use crate::Result;
+
+/// Loads the interface of a Simulink model from a folder, a header file or an interface lockfile
+fn load_interface(path: &Path, recursive: bool) -> Result<Interface> {
+    if path.is_dir() || path.extension().is_some_and(|extension| extension == "h") {
+        Ok(Interface::from(&crate::load_model(path, recursive)?))
+    } else {
+        Ok(Interface::read(path)?)
+    }
+}
+
+/// Prints the changes of the interface from the model in `old` to the model in `new`
+pub fn diff(old: &Path, new: &Path, recursive: bool, json: bool) -> Result<()> {
+    let changes = load_interface(old, recursive)?.diff(&load_interface(new, recursive)?);
+    let bump = if changes.iter().any(|change| change.is_breaking()) {
+        "major"
+    } else if changes.is_empty() {
+        "none"
+    } else {
+        "minor"
+    };
+    if json {
+        let changes: Vec<_> = changes
+            .iter()
+            .map(|change| json!({"breaking": change.is_breaking(), "description": change.to_string(), "change": change}))
+            .collect();
+        let output = json!({"changes": changes, "bump": bump});
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+    for change in &changes {
+        let label = if change.is_breaking() {
+            "breaking"
+        } else {
+            "non-breaking"
+        };
+        println!("{:<14}{}", label, change);
+    }
+    println!("Suggested version bump: {}", bump);
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use serde_json::{json, Value};
+use simulink_model::{Model, Simulink};
+use std::path::Path;
+
+use crate::Result;
+
+/// Prints the interface of the Simulink model in `path`, either a folder or a header file
+pub fn inspect(path: &Path, recursive: bool, json: bool) -> Result<()> {
+    let model = crate::load_model(path, recursive)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&to_json(&model))?);
+    } else {
+        print_table(&model);
+    }
+    Ok(())
+}
+
+/// Returns the properties of a Simulink structure as a JSON array
+fn properties(simulink: Option<&Simulink>) -> Value {
+    simulink
+        .map(|simulink| {
+            simulink
+                .properties
+                .iter()
+                .map(|io| json!({"name": io.name, "dtype": io.dtype, "size": io.size.unwrap_or(1)}))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn to_json(model: &Model) -> Value {
+    json!({
+        "name": model.name,
+        "metadata": {
+            "model_version": model.metadata.model_version,
+            "coder_version": model.metadata.coder_version,
+            "generated_on": model.metadata.generated_on,
+            "target": model.metadata.target,
+        },
+        "sample_time": model.sample_time,
+        "inputs": properties(model.inputs()),
+        "outputs": properties(model.outputs()),
+        "states": properties(model.states()),
+        "parameters": properties(model.parameters()),
+        "entry_points": model.symbols.functions,
+    })
+}
+
+fn print_table(model: &Model) {
+    let na = || "n/a".to_string();
+    let metadata = &model.metadata;
+    println!("Model: {}", model.name);
+    println!(
+        "  {:<22}: {}",
+        "model version",
+        metadata.model_version.clone().unwrap_or_else(na)
+    );
+    println!(
+        "  {:<22}: {}",
+        "Simulink Coder version",
+        metadata.coder_version.clone().unwrap_or_else(na)
+    );
+    println!(
+        "  {:<22}: {}",
+        "generated on",
+        metadata.generated_on.clone().unwrap_or_else(na)
+    );
+    println!(
+        "  {:<22}: {}",
+        "target",
+        metadata.target.clone().unwrap_or_else(na)
+    );
+    println!(
+        "  {:<22}: {}",
+        "sample time",
+        model
+            .sample_time
+            .map(|sample_time| format!("{}s", sample_time))
+            .unwrap_or_else(na)
+    );
+    for (title, simulink) in [
+        ("Inputs", model.inputs()),
+        ("Outputs", model.outputs()),
+        ("States", model.states()),
+        ("Parameters", model.parameters()),
+    ] {
+        let Some(simulink) = simulink else {
+            continue;
+        };
+        println!();
+        println!("{} ({})", title, simulink.name);
+        println!("  {:<32} {:<12} {:>6}", "name", "dtype", "size");
+        for io in simulink.properties.iter() {
+            println!(
+                "  {:<32} {:<12} {:>6}",
+                io.name,
+                io.dtype,
+                io.size.unwrap_or(1)
+            );
+        }
+    }
+    println!();
+    println!("Entry points");
+    for function in &model.symbols.functions {
+        println!("  {}", function);
+    }
+}
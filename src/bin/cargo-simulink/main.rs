@@ -0,0 +1,111 @@
+//! # cargo simulink
+//!
+//! Cargo subcommand to manage Simulink C model crates
+//!
+//! ```shell
+//! cargo install simulink-rs --features cli
+//! cargo simulink new m1-sa-control --from M1SA_Control_OA_ert_rtw --name M1SAControl
+//! cargo simulink inspect m1-sa-control/sys
+//! cargo simulink diff m1-sa-control/simulink.lock M1SA_Control_OA_ert_rtw
+//! ```
+
+use clap::{Args, Parser, Subcommand};
+use simulink_model::Model;
+use std::path::{Path, PathBuf};
+
+mod diff;
+mod inspect;
+mod new;
+
+#[derive(Parser)]
+#[command(name = "cargo", bin_name = "cargo")]
+enum Cargo {
+    Simulink(Simulink),
+}
+
+/// Manages Simulink C model crates
+#[derive(Args)]
+#[command(version)]
+struct Simulink {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Creates a new model crate from a Simulink code generation folder
+    New {
+        /// Path of the new crate
+        path: PathBuf,
+        /// Simulink code generation folder with the C header and source files
+        #[arg(long)]
+        from: PathBuf,
+        /// Name of the Rust controller type, derived from the model name if not set
+        #[arg(long)]
+        name: Option<String>,
+        /// Searches the sub-folders of the code generation folder for C files
+        #[arg(long)]
+        recursive: bool,
+    },
+    /// Prints the interface of a Simulink model
+    Inspect {
+        /// Folder with the C header and source files or main header file
+        path: PathBuf,
+        /// Searches the sub-folders for C files
+        #[arg(long)]
+        recursive: bool,
+        /// Prints the interface as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compares the interfaces of two Simulink models and classifies the changes
+    Diff {
+        /// Old folder with the C header and source files, main header file or interface lockfile
+        old: PathBuf,
+        /// New folder with the C header and source files, main header file or interface lockfile
+        new: PathBuf,
+        /// Searches the sub-folders for C files
+        #[arg(long)]
+        recursive: bool,
+        /// Prints the changes as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Parses the Simulink model in `path`, either a folder or a header file
+fn load_model(path: &Path, recursive: bool) -> Result<Model> {
+    let (headers, sources) = if path.is_dir() {
+        simulink_model::c_files(path, recursive)
+    } else {
+        (vec![path.to_path_buf()], vec![])
+    };
+    let header = simulink_model::main_header(&headers, &sources)
+        .ok_or_else(|| format!("cannot find the main header file in {:?}", path))?;
+    Ok(Model::from_files(header, &headers, &sources)?)
+}
+
+fn main() -> Result<()> {
+    let Cargo::Simulink(simulink) = Cargo::parse();
+    match simulink.command {
+        Command::New {
+            path,
+            from,
+            name,
+            recursive,
+        } => new::new(&path, &from, name, recursive),
+        Command::Inspect {
+            path,
+            recursive,
+            json,
+        } => inspect::inspect(&path, recursive, json),
+        Command::Diff {
+            old,
+            new,
+            recursive,
+            json,
+        } => diff::diff(&old, &new, recursive, json),
+    }
+}
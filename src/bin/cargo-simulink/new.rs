@@ -0,0 +1,365 @@
+use simulink_model::Model;
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+use toml_edit::{value, Array, DocumentMut, InlineTable, Item, Table, Value};
+
+use crate::Result;
+
+const CARGO_TOML: &str = include_str!("../../../template/Cargo.toml");
+const BUILD_RS: &str = include_str!("../../../template/build.rs");
+const LIB_RS: &str = include_str!("../../../template/src/lib.rs");
+
+/// Creates a new model crate at `path` from the Simulink code generation folder `from`
+pub fn new(path: &Path, from: &Path, name: Option<String>, recursive: bool) -> Result<()> {
+    let (headers, sources) = simulink_model::c_files(from, recursive);
+    let header = simulink_model::main_header(&headers, &sources)
+        .ok_or_else(|| format!("cannot find the main header file in {:?}", from))?;
+    let model = Model::from_files(header, &headers, &sources)?;
+    let rs_type = name.unwrap_or_else(|| type_name(&model.name));
+    let copies = copies(&headers, &sources)?;
+
+    if path.exists() {
+        return Err(format!("{:?} already exists", path).into());
+    }
+    let crate_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("invalid crate path {:?}", path))?
+        .to_string();
+
+    let sys = path.join("sys");
+    fs::create_dir_all(&sys)?;
+    fs::create_dir_all(path.join("src"))?;
+    fs::create_dir_all(path.join("tests"))?;
+    for (file_name, file) in copies {
+        fs::copy(file, sys.join(file_name))?;
+    }
+
+    let path = path.canonicalize()?;
+    let workspace = path.parent().and_then(find_workspace);
+
+    fs::write(
+        path.join("Cargo.toml"),
+        manifest(&crate_name, workspace.as_ref().map(|(_, doc)| doc))?,
+    )?;
+    fs::write(
+        path.join("build.rs"),
+        BUILD_RS.replace(r#"Some("...")"#, &format!("Some({:?})", rs_type)),
+    )?;
+    fs::write(path.join("src").join("lib.rs"), LIB_RS)?;
+    fs::write(
+        path.join("tests").join("smoke.rs"),
+        format!(
+            r#"#[test]
+fn smoke() {{
+    let mut controller = {}::{}::new();
+    controller.step();
+}}
+"#,
+            lib_name(&crate_name),
+            rs_type
+        ),
+    )?;
+
+    if let Some((root, mut doc)) = workspace {
+        match path.strip_prefix(&root) {
+            Ok(member) => {
+                add_member(&mut doc, member)?;
+                fs::write(root.join("Cargo.toml"), doc.to_string())?;
+                println!("Added {:?} to the workspace {:?}", member, root);
+            }
+            Err(_) => println!("{:?} is not within the workspace {:?}", path, root),
+        }
+    }
+    println!(
+        "Created crate {} wrapping the Simulink model {} as {}",
+        crate_name, model.name, rs_type
+    );
+    Ok(())
+}
+
+/// Maps the C files to their names in `sys`
+///
+/// The files are copied flat into `sys`, where the build script looks for them,
+/// so two files with the same name are an error
+fn copies<'a>(
+    headers: &'a [PathBuf],
+    sources: &'a [PathBuf],
+) -> Result<HashMap<&'a OsStr, &'a Path>> {
+    let mut copies: HashMap<&OsStr, &Path> = HashMap::new();
+    for file in headers.iter().chain(sources) {
+        let file_name = file
+            .file_name()
+            .ok_or_else(|| format!("invalid C file path {:?}", file))?;
+        if let Some(other) = copies.insert(file_name, file) {
+            return Err(format!(
+                "{:?} and {:?} would both be copied to sys/{}",
+                other,
+                file,
+                file_name.to_string_lossy()
+            )
+            .into());
+        }
+    }
+    Ok(copies)
+}
+
+/// Returns the name of the library target of the crate, e.g. `m1_sa_control` for `m1-sa-control`
+fn lib_name(crate_name: &str) -> String {
+    crate_name.replace('-', "_")
+}
+
+/// Converts the Simulink model name into a Rust type name, e.g. `M1SA_Control_OA` into `M1SAControlOA`
+fn type_name(model: &str) -> String {
+    model
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Looks for the Cargo workspace that `dir` belongs to
+///
+/// Returns the workspace root directory and its manifest
+fn find_workspace(dir: &Path) -> Option<(PathBuf, DocumentMut)> {
+    dir.ancestors().find_map(|dir| {
+        let doc: DocumentMut = fs::read_to_string(dir.join("Cargo.toml"))
+            .ok()?
+            .parse()
+            .ok()?;
+        doc.contains_key("workspace")
+            .then(|| (dir.to_path_buf(), doc))
+    })
+}
+
+/// Checks if a manifest item is inherited from the workspace (`key.workspace = true`)
+fn is_inherited(item: &Item) -> bool {
+    item.as_table_like()
+        .and_then(|table| table.get("workspace"))
+        .and_then(|item| item.as_bool())
+        .unwrap_or_default()
+}
+
+/// Writes the manifest of the new crate from the template
+///
+/// The template fields and dependencies that are inherited from the workspace are kept
+/// only if the workspace defines them
+fn manifest(crate_name: &str, workspace: Option<&DocumentMut>) -> Result<String> {
+    let mut doc: DocumentMut = CARGO_TOML.parse()?;
+    doc["package"]["name"] = value(crate_name);
+    // the smoke test refers to the library by this name
+    let mut lib = Table::new();
+    lib.insert("name", value(lib_name(crate_name)));
+    lib.set_position(
+        doc["package"]
+            .as_table()
+            .and_then(|package| package.position())
+            .unwrap_or_default(),
+    );
+    doc.insert("lib", Item::Table(lib));
+
+    let workspace_package = workspace.and_then(|doc| doc.get("workspace")?.get("package"));
+    let package = doc["package"].as_table_mut().unwrap();
+    let inherited: Vec<_> = package
+        .iter()
+        .filter(|(_, item)| is_inherited(item))
+        .map(|(key, _)| key.to_string())
+        .collect();
+    for key in inherited {
+        if workspace_package.is_some_and(|package| package.get(&key).is_some()) {
+            continue;
+        }
+        match key.as_str() {
+            "edition" => package[&key] = value("2021"),
+            _ => {
+                package.remove(&key);
+            }
+        }
+    }
+
+    let workspace_dependencies =
+        workspace.and_then(|doc| doc.get("workspace")?.get("dependencies"));
+    let has_workspace_dependency = |name: &str| {
+        workspace_dependencies.is_some_and(|dependencies| dependencies.get(name).is_some())
+    };
+    for section in ["dependencies", "build-dependencies", "dev-dependencies"] {
+        let Some(dependencies) = doc.get_mut(section).and_then(|item| item.as_table_mut()) else {
+            continue;
+        };
+        let inherited: Vec<_> = dependencies
+            .iter()
+            .filter(|(_, item)| is_inherited(item))
+            .map(|(key, _)| key.to_string())
+            .collect();
+        for name in inherited {
+            if has_workspace_dependency(&name) {
+                continue;
+            }
+            match name.as_str() {
+                "simulink-rs" => {
+                    // the features, e.g. `mat` in the dev-dependencies, are kept
+                    let features = dependencies[&name]
+                        .as_table_like()
+                        .and_then(|table| table.get("features"))
+                        .and_then(|item| item.as_value())
+                        .cloned();
+                    dependencies[&name] = match features {
+                        Some(features) => {
+                            let mut table = InlineTable::new();
+                            table.insert("version", Value::from(env!("CARGO_PKG_VERSION")));
+                            table.insert("features", features);
+                            value(table)
+                        }
+                        None => value(env!("CARGO_PKG_VERSION")),
+                    }
+                }
+                _ => {
+                    dependencies.remove(&name);
+                }
+            }
+        }
+        if section == "dependencies" && !dependencies.contains_key("serde") {
+            // the generated code derives serde traits
+            dependencies["serde"] = if has_workspace_dependency("serde") {
+                let mut table = InlineTable::new();
+                table.insert("workspace", Value::from(true));
+                value(table)
+            } else {
+                value("1.0")
+            };
+        }
+        if dependencies.is_empty() {
+            doc.remove(section);
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Adds a member to the workspace manifest
+fn add_member(doc: &mut DocumentMut, member: &Path) -> Result<()> {
+    let member = member
+        .to_str()
+        .ok_or_else(|| format!("invalid member path {:?}", member))?;
+    let workspace = doc["workspace"]
+        .as_table_mut()
+        .ok_or("invalid workspace table")?;
+    let members = workspace
+        .entry("members")
+        .or_insert_with(|| value(Array::new()))
+        .as_array_mut()
+        .ok_or("invalid workspace members")?;
+    if !members.iter().any(|m| m.as_str() == Some(member)) {
+        members.push(member);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_names() {
+        assert_eq!(type_name("M1SA_Control_OA"), "M1SAControlOA");
+        assert_eq!(type_name("ctypes__model_"), "CtypesModel");
+    }
+
+    #[test]
+    fn standalone_manifest() {
+        let doc: DocumentMut = manifest("m1-sa-control", None).unwrap().parse().unwrap();
+        assert_eq!(doc["package"]["name"].as_str(), Some("m1-sa-control"));
+        assert_eq!(doc["package"]["edition"].as_str(), Some("2021"));
+        assert!(doc["package"].get("license").is_none());
+        assert_eq!(doc["lib"]["name"].as_str(), Some("m1_sa_control"));
+        assert_eq!(
+            doc["dependencies"]["simulink-rs"].as_str(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+        assert_eq!(doc["dependencies"]["serde"].as_str(), Some("1.0"));
+        assert_eq!(
+            doc["dev-dependencies"]["simulink-rs"]["features"]
+                .as_array()
+                .and_then(|features| features.get(0))
+                .and_then(|feature| feature.as_str()),
+            Some("mat")
+        );
+    }
+
+    #[test]
+    fn workspace_manifest() {
+        let workspace: DocumentMut = r#"
+[workspace]
+members = ["m1-sa-control"]
+
+[workspace.package]
+edition = "2021"
+license = "MIT"
+
+[workspace.dependencies]
+simulink-rs = "4"
+"#
+        .parse()
+        .unwrap();
+        let doc: DocumentMut = manifest("m2-control", Some(&workspace))
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(doc["package"]["name"].as_str(), Some("m2-control"));
+        assert_eq!(doc["lib"]["name"].as_str(), Some("m2_control"));
+        // the template fields and dependencies defined by the workspace are kept
+        assert!(is_inherited(&doc["package"]["edition"]));
+        assert!(is_inherited(&doc["package"]["license"]));
+        assert!(doc["package"].get("repository").is_none());
+        for section in ["dependencies", "build-dependencies", "dev-dependencies"] {
+            assert!(is_inherited(&doc[section]["simulink-rs"]));
+        }
+        assert_eq!(doc["dependencies"]["serde"].as_str(), Some("1.0"));
+    }
+
+    #[test]
+    fn workspace_members() {
+        let mut doc: DocumentMut = "[workspace]\nmembers = [\"m1-sa-control\"]\n"
+            .parse()
+            .unwrap();
+        add_member(&mut doc, Path::new("m2-control")).unwrap();
+        add_member(&mut doc, Path::new("m1-sa-control")).unwrap();
+        let members: Vec<_> = doc["workspace"]["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|member| member.as_str())
+            .collect();
+        assert_eq!(members, vec!["m1-sa-control", "m2-control"]);
+
+        let mut doc: DocumentMut = "[workspace]\n".parse().unwrap();
+        add_member(&mut doc, Path::new("m2-control")).unwrap();
+        assert_eq!(doc["workspace"]["members"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn duplicate_file_names() {
+        let headers = vec![
+            PathBuf::from("sys/model.h"),
+            PathBuf::from("sys/sub/util.h"),
+        ];
+        let sources = vec![PathBuf::from("sys/model.c")];
+        assert_eq!(copies(&headers, &sources).unwrap().len(), 3);
+
+        let sources = vec![
+            PathBuf::from("sys/model.c"),
+            PathBuf::from("sys/sub/model.c"),
+        ];
+        let err = copies(&headers, &sources).unwrap_err();
+        assert!(err.to_string().contains("sys/model.c"));
+    }
+}
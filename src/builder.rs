@@ -0,0 +1,358 @@
+use glob::{MatchOptions, Pattern};
+use regex::Regex;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{
+    generator::Generators, Allowlist, Compiler, Generator, InterfaceLock, OptLevel, Sanitizer, Sys,
+    Template,
+};
+
+/// Names of the example main files generated by Simulink Coder
+const EXAMPLE_MAINS: [&str; 4] = ["ert_main.c", "grt_main.c", "rt_main.c", "classic_main.c"];
+
+/// Simulink C to Rust wrapper builder
+///
+/// # Example
+/// ```ignore
+/// let sys = Sys::builder()
+///     .name("MySimulinkController")
+///     .recursive(true)
+///     .exclude("**/test_*.c")
+///     .include_dir("slprj/_sharedutils")
+///     .include_dir_from_env("MATLAB_ROOT", "extern/include")
+///     .define("MODEL", Some("MySimulinkModel"))
+///     .flag("-ffp-contract=off")
+///     .opt_level(OptLevel::O3)
+///     .build();
+/// sys.compile().generate_module();
+/// ```
+pub struct Builder {
+    controller_type: Option<String>,
+    sys_folder: String,
+    recursive: bool,
+    includes: Vec<String>,
+    excludes: Vec<String>,
+    exclude_example_mains: bool,
+    include_dirs: Vec<PathBuf>,
+    sources: Vec<PathBuf>,
+    headers: Vec<PathBuf>,
+    compiler: Compiler,
+    generated_dir: Option<PathBuf>,
+    allowlist: Allowlist,
+    lock: InterfaceLock,
+    lockfile: Option<PathBuf>,
+    generators: Generators,
+}
+impl Default for Builder {
+    fn default() -> Self {
+        Self {
+            controller_type: Default::default(),
+            sys_folder: "sys".into(),
+            recursive: false,
+            includes: Default::default(),
+            excludes: Default::default(),
+            exclude_example_mains: true,
+            include_dirs: Default::default(),
+            sources: Default::default(),
+            headers: Default::default(),
+            compiler: Default::default(),
+            generated_dir: Default::default(),
+            allowlist: Default::default(),
+            lock: Default::default(),
+            lockfile: Default::default(),
+            generators: Default::default(),
+        }
+    }
+}
+impl Builder {
+    /// Sets the name of the Rust structure that acts as a wrapper for the Simulink C code
+    ///
+    /// If not set, the structure is given the same name than the Simulink control model
+    pub fn name<S: Into<String>>(mut self, rs_type: S) -> Self {
+        self.controller_type = Some(rs_type.into());
+        self
+    }
+    /// Sets the name of the folder with the C header and source file
+    ///
+    /// If not set, expect the folder to be named "sys"
+    pub fn folder<S: Into<String>>(mut self, folder: S) -> Self {
+        self.sys_folder = folder.into();
+        self
+    }
+    /// Searches the sub-folders of the sys folder for C header and source files
+    ///
+    /// Disabled by default
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+    /// Adds a glob pattern that the C header and source files must match
+    ///
+    /// Patterns are matched against the file paths relative to the sys folder.
+    /// If no pattern is given, all the C header and source files are selected
+    pub fn glob<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.includes.push(pattern.into());
+        self
+    }
+    /// Adds a glob pattern for the C header and source files to leave out
+    ///
+    /// Patterns are matched against the file paths relative to the sys folder
+    pub fn exclude<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.excludes.push(pattern.into());
+        self
+    }
+    /// Keeps the source files that define a `main` function
+    ///
+    /// By default, the example mains generated by Simulink Coder (e.g. `ert_main.c`)
+    /// are left out as they break the link
+    pub fn keep_example_mains(mut self) -> Self {
+        self.exclude_example_mains = false;
+        self
+    }
+    /// Adds a directory to the list of C include directories
+    ///
+    /// Relative paths are resolved from the crate root directory
+    pub fn include_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.include_dirs.push(manifest_dir().join(dir));
+        self
+    }
+    /// Adds the directory `dir`, relative to the path given by the environment variable `var`,
+    /// to the list of C include directories
+    ///
+    /// The directory is skipped with a warning if the environment variable is not set,
+    /// e.g. `include_dir_from_env("MATLAB_ROOT", "extern/include")`
+    pub fn include_dir_from_env<S: AsRef<str>, P: AsRef<Path>>(mut self, var: S, dir: P) -> Self {
+        let var = var.as_ref();
+        println!("cargo:rerun-if-env-changed={}", var);
+        match env::var_os(var) {
+            Some(root) => self.include_dirs.push(Path::new(&root).join(dir)),
+            None => println!(
+                "cargo:warning=environment variable {} is not set, skipping include directory {:?}",
+                var,
+                dir.as_ref()
+            ),
+        }
+        self
+    }
+    /// Adds a C source file to the list of files to compile
+    ///
+    /// Relative paths are resolved from the crate root directory
+    pub fn source<P: AsRef<Path>>(mut self, source: P) -> Self {
+        self.sources.push(manifest_dir().join(source));
+        self
+    }
+    /// Adds a C header file to the list of files to pass to bindgen
+    ///
+    /// Relative paths are resolved from the crate root directory
+    pub fn header<P: AsRef<Path>>(mut self, header: P) -> Self {
+        self.headers.push(manifest_dir().join(header));
+        self
+    }
+    /// Adds a C preprocessor definition, e.g. `define("MODEL", Some("M1SA_Control_OA"))`
+    /// or `define("TID01EQ", None::<&str>)`
+    pub fn define<N: AsRef<str>, V: AsRef<str>>(mut self, name: N, value: Option<V>) -> Self {
+        self.compiler.defines.push((
+            name.as_ref().to_string(),
+            value.map(|value| value.as_ref().to_string()),
+        ));
+        self
+    }
+    /// Adds a flag to the C compiler, e.g. `flag("-ffp-contract=off")`
+    pub fn flag<S: Into<String>>(mut self, flag: S) -> Self {
+        self.compiler.flags.push(flag.into());
+        self
+    }
+    /// Sets the C compiler optimization level
+    ///
+    /// If not set, the optimization level is derived from the cargo profile
+    pub fn opt_level(mut self, opt_level: OptLevel) -> Self {
+        self.compiler.opt_level = Some(opt_level);
+        self
+    }
+    /// Enables or disables the C compiler warnings
+    pub fn warnings(mut self, warnings: bool) -> Self {
+        self.compiler.warnings = Some(warnings);
+        self
+    }
+    /// Turns the C compiler warnings into errors (`-Werror`)
+    pub fn warnings_into_errors(mut self, warnings_into_errors: bool) -> Self {
+        self.compiler.warnings_into_errors = warnings_into_errors;
+        self
+    }
+    /// Instruments the C code with a runtime sanitizer
+    ///
+    /// Only the C code is instrumented: the sanitizer runtime library is not available with
+    /// every toolchain, so it is not linked by the build script and must be linked with
+    /// `RUSTFLAGS`, e.g. `RUSTFLAGS="-Clink-arg=-fsanitize=address"`
+    pub fn sanitizer(mut self, sanitizer: Sanitizer) -> Self {
+        if !self.compiler.sanitizers.contains(&sanitizer) {
+            self.compiler.sanitizers.push(sanitizer);
+        }
+        self
+    }
+    /// Sets the directory where the bindings and the controller module are pre-generated
+    ///
+    /// The directory is meant to be checked in, so the crate can be built without bindgen,
+    /// relative paths are resolved from the crate root directory
+    pub fn generated_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.generated_dir = Some(manifest_dir().join(dir));
+        self
+    }
+    /// Adds a type pattern to the bindgen allowlist
+    ///
+    /// The types, functions and variables of the Simulink model are allowlisted by default
+    pub fn allowlist_type<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.allowlist.types.push(pattern.into());
+        self
+    }
+    /// Adds a function pattern to the bindgen allowlist
+    pub fn allowlist_function<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.allowlist.functions.push(pattern.into());
+        self
+    }
+    /// Adds a variable pattern to the bindgen allowlist
+    pub fn allowlist_var<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.allowlist.variables.push(pattern.into());
+        self
+    }
+    /// Adds a type pattern to the bindgen blocklist
+    pub fn blocklist_type<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.allowlist.blocked_types.push(pattern.into());
+        self
+    }
+    /// Adds a function pattern to the bindgen blocklist
+    pub fn blocklist_function<S: Into<String>>(mut self, pattern: S) -> Self {
+        self.allowlist.blocked_functions.push(pattern.into());
+        self
+    }
+    /// Sets the interface lockfile mode
+    ///
+    /// Disabled by default
+    pub fn lock_interface(mut self, lock: InterfaceLock) -> Self {
+        self.lock = lock;
+        self
+    }
+    /// Sets the path to the interface lockfile
+    ///
+    /// If not set, the lockfile is `simulink.lock` next to the sys folder,
+    /// relative paths are resolved from the crate root directory
+    pub fn lockfile<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.lockfile = Some(manifest_dir().join(path));
+        self
+    }
+    /// Adds a generator of extra Rust code, written into `OUT_DIR` by [Sys::compile]
+    pub fn generator<G: Generator + 'static>(mut self, generator: G) -> Self {
+        self.generators.0.push(Arc::new(generator));
+        self
+    }
+    /// Adds a template file rendered against the model, written into `OUT_DIR` by [Sys::compile]
+    ///
+    /// Relative paths are resolved from the crate root directory, see [Template]
+    pub fn template<P: AsRef<Path>>(self, path: P) -> Self {
+        self.generator(Template::new(manifest_dir().join(path)))
+    }
+    /// Builds a new Simulink C to Rust wrapper
+    pub fn build(self) -> Sys {
+        let sys = manifest_dir().join(&self.sys_folder);
+
+        let includes = patterns(&self.includes);
+        let excludes = patterns(&self.excludes);
+        let options = MatchOptions {
+            require_literal_separator: false,
+            ..Default::default()
+        };
+
+        let (sys_headers, sys_sources) = simulink_model::c_files(&sys, self.recursive);
+
+        let mut sources = self.sources;
+        let mut headers = self.headers;
+        let mut example_mains = vec![];
+        for file in sys_headers.into_iter().chain(sys_sources) {
+            let relative = file.strip_prefix(&sys).unwrap_or(&file);
+            if !includes.is_empty()
+                && !includes
+                    .iter()
+                    .any(|pattern| pattern.matches_path_with(relative, options))
+            {
+                continue;
+            }
+            if excludes
+                .iter()
+                .any(|pattern| pattern.matches_path_with(relative, options))
+            {
+                continue;
+            }
+            match file.extension().and_then(|extension| extension.to_str()) {
+                Some("c") => {
+                    if self.exclude_example_mains && is_example_main(&file) {
+                        println!("cargo:warning=skipping example main {:?}", file);
+                        example_mains.push(file);
+                        continue;
+                    }
+                    sources.push(file);
+                }
+                Some("h") => {
+                    headers.push(file);
+                }
+                _ => (),
+            }
+        }
+
+        // the folders of the headers are searched for the includes of other headers
+        let mut include_dirs = self.include_dirs;
+        for dir in headers.iter().filter_map(|header| header.parent()) {
+            if !include_dirs.iter().any(|include_dir| include_dir == dir) {
+                include_dirs.push(dir.to_path_buf());
+            }
+        }
+
+        Sys {
+            controller: self.controller_type,
+            folder: sys.clone(),
+            sources,
+            headers,
+            include_dirs,
+            compiler: self.compiler,
+            generated_dir: self.generated_dir,
+            allowlist: self.allowlist,
+            example_mains,
+            lock: self.lock,
+            lockfile: self
+                .lockfile
+                .unwrap_or_else(|| sys.parent().unwrap_or(&sys).join("simulink.lock")),
+            generators: self.generators,
+        }
+    }
+}
+
+/// Returns the crate root directory
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap())
+}
+
+/// Compiles the glob patterns
+fn patterns(patterns: &[String]) -> Vec<Pattern> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Pattern::new(pattern).unwrap_or_else(|e| panic!("invalid glob {:?}: {}", pattern, e))
+        })
+        .collect()
+}
+
+/// Checks if a source file is an example main, either by name or by looking for a `main` definition
+fn is_example_main(source: &Path) -> bool {
+    if source
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| EXAMPLE_MAINS.contains(&name))
+    {
+        return true;
+    }
+    let regex = Regex::new(r"(?m)^\s*(?:int|int_T|int32_T)\s+main\s*\(").unwrap();
+    fs::read_to_string(source).is_ok_and(|code| regex.is_match(&code))
+}
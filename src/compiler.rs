@@ -0,0 +1,100 @@
+use std::fmt::Display;
+
+/// C compiler optimization level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptLevel {
+    /// No optimization (`-O0`)
+    O0,
+    /// `-O1`
+    O1,
+    /// `-O2`
+    O2,
+    /// `-O3`
+    O3,
+    /// Optimization for size (`-Os`)
+    Os,
+}
+impl Display for OptLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptLevel::O0 => write!(f, "0"),
+            OptLevel::O1 => write!(f, "1"),
+            OptLevel::O2 => write!(f, "2"),
+            OptLevel::O3 => write!(f, "3"),
+            OptLevel::Os => write!(f, "s"),
+        }
+    }
+}
+
+/// C compiler runtime sanitizers
+///
+/// The runtime library of the sanitizer must be linked with `RUSTFLAGS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sanitizer {
+    /// AddressSanitizer (`-fsanitize=address`)
+    Address,
+    /// UndefinedBehaviorSanitizer (`-fsanitize=undefined`)
+    Undefined,
+}
+impl Display for Sanitizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Sanitizer::Address => write!(f, "address"),
+            Sanitizer::Undefined => write!(f, "undefined"),
+        }
+    }
+}
+
+/// C compiler configuration
+///
+/// The configuration is applied to both the `cc` compiler and to the bindgen clang arguments
+#[derive(Debug, Default, Clone)]
+pub struct Compiler {
+    pub(crate) defines: Vec<(String, Option<String>)>,
+    pub(crate) flags: Vec<String>,
+    pub(crate) opt_level: Option<OptLevel>,
+    pub(crate) warnings: Option<bool>,
+    pub(crate) warnings_into_errors: bool,
+    pub(crate) sanitizers: Vec<Sanitizer>,
+}
+impl Compiler {
+    /// Configures the `cc` builder
+    pub(crate) fn configure(&self, cc_builder: &mut cc::Build) {
+        for (name, value) in &self.defines {
+            cc_builder.define(name, value.as_deref());
+        }
+        for flag in &self.flags {
+            cc_builder.flag(flag);
+        }
+        if let Some(opt_level) = self.opt_level {
+            cc_builder.opt_level_str(&opt_level.to_string());
+        }
+        if let Some(warnings) = self.warnings {
+            cc_builder.warnings(warnings);
+        }
+        cc_builder.warnings_into_errors(self.warnings_into_errors);
+        for sanitizer in &self.sanitizers {
+            cc_builder
+                .flag(format!("-fsanitize={}", sanitizer))
+                .flag("-fno-omit-frame-pointer");
+        }
+    }
+    /// Returns the clang arguments for bindgen
+    ///
+    /// Warnings and sanitizers settings only apply to the compiled code and are not forwarded to clang
+    pub fn clang_args(&self) -> Vec<String> {
+        let mut args: Vec<_> = self
+            .defines
+            .iter()
+            .map(|(name, value)| match value {
+                Some(value) => format!("-D{}={}", name, value),
+                None => format!("-D{}", name),
+            })
+            .collect();
+        args.extend(self.flags.iter().cloned());
+        if let Some(opt_level) = self.opt_level {
+            args.push(format!("-O{}", opt_level));
+        }
+        args
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{
+    snapshot::{Snapshot, SnapshotError},
+    Patch, SignalError, SignalInfo, Wire,
+};
+
+/// Conversion of a Simulink structure from and to a contiguous `f64` slice
+///
+/// The properties of a numeric type are flattened in order, the other properties are left out
+pub trait Flatten {
+    /// Length of the flattened structure
+    const LEN: usize;
+    /// Sets the structure from a slice
+    ///
+    /// Returns an error if the length of `data` is not [Flatten::LEN]
+    fn copy_from_slice(&mut self, data: &[f64]) -> Result<(), SignalError>;
+    /// Copies the structure into a slice
+    ///
+    /// Returns an error if the length of `data` is not [Flatten::LEN]
+    fn copy_to_slice(&self, data: &mut [f64]) -> Result<(), SignalError>;
+    /// Returns the flattened structure
+    fn to_vec(&self) -> Vec<f64> {
+        let mut data = vec![0f64; Self::LEN];
+        let _ = self.copy_to_slice(&mut data);
+        data
+    }
+}
+
+/// Simulink model information
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelInfo {
+    /// Simulink model name
+    pub name: &'static str,
+    /// Simulink model version
+    pub model_version: Option<&'static str>,
+    /// Simulink Coder version
+    pub coder_version: Option<&'static str>,
+    /// Date of the C code generation
+    pub generated_on: Option<&'static str>,
+    /// Base sample time in seconds
+    pub sample_time: Option<f64>,
+    /// Hash of the model interface, see [Interface::hash](crate::Interface::hash)
+    pub interface_hash: u64,
+}
+
+/// Interface to the Simulink controllers
+///
+/// The trait is implemented by the Rust wrapper of every Simulink C controller,
+/// so code can be written generically over controllers
+///
+/// # Example
+/// ```ignore
+/// fn run<C: SimulinkController>(controller: &mut C, inputs: &[C::Inputs]) -> Vec<C::Outputs>
+/// where
+///     C::Inputs: Copy,
+///     C::Outputs: Copy,
+/// {
+///     inputs
+///         .iter()
+///         .map(|inputs| {
+///             *controller.inputs_mut() = *inputs;
+///             controller.step();
+///             *controller.outputs()
+///         })
+///         .collect()
+/// }
+/// ```
+pub trait SimulinkController {
+    /// Inputs Simulink structure
+    type Inputs: Flatten + Patch + Wire;
+    /// Outputs Simulink structure
+    type Outputs: Flatten + Patch + Wire;
+    /// States Simulink structure
+    type States: Flatten + Patch + Wire;
+    /// Creates a new controller
+    fn new() -> Self;
+    /// Steps the controller
+    fn step(&mut self);
+    /// Resets the inputs, outputs and states of the controller to their initial values
+    fn reset(&mut self);
+    /// Returns the inputs
+    fn inputs(&self) -> &Self::Inputs;
+    /// Returns the inputs for writing
+    fn inputs_mut(&mut self) -> &mut Self::Inputs;
+    /// Returns the outputs
+    fn outputs(&self) -> &Self::Outputs;
+    /// Returns the outputs for writing, e.g. to restore a [Snapshot]
+    fn outputs_mut(&mut self) -> &mut Self::Outputs;
+    /// Returns the states
+    fn states(&self) -> &Self::States;
+    /// Returns the inputs and outputs signals of the controller
+    fn signals(&self) -> &'static [SignalInfo];
+    /// Returns the values of an input or output signal given its Simulink or snake case name
+    ///
+    /// Returns `None` if the signal does not exist or is not a `f64` signal
+    fn get(&self, name: &str) -> Option<&[f64]>;
+    /// Sets the values of an input signal given its Simulink or snake case name
+    fn set(&mut self, name: &str, value: &[f64]) -> Result<(), SignalError>;
+    /// Returns the states for writing, e.g. to restore a [Snapshot]
+    fn states_mut(&mut self) -> &mut Self::States;
+    /// Steps the controller `n` times
+    ///
+    /// `before(i, inputs)` is called before the step `i` to set the inputs,
+    /// and `after(i, outputs, states)` after it.
+    /// The real-time model structure of the C code is built once for all the steps
+    fn run<B, A>(&mut self, n: usize, mut before: B, after: A)
+    where
+        B: FnMut(usize, &mut Self::Inputs),
+        A: FnMut(usize, &Self::Outputs, &Self::States),
+    {
+        self.run_while(
+            |i, inputs| {
+                if i < n {
+                    before(i, inputs);
+                }
+                i < n
+            },
+            after,
+        )
+    }
+    /// Steps the controller as long as `before(i, inputs)` returns `true`
+    ///
+    /// `before(i, inputs)` is called before the step `i` to set the inputs,
+    /// and `after(i, outputs, states)` after it.
+    /// The real-time model structure of the C code is built once for all the steps
+    fn run_while<B, A>(&mut self, before: B, after: A)
+    where
+        B: FnMut(usize, &mut Self::Inputs) -> bool,
+        A: FnMut(usize, &Self::Outputs, &Self::States);
+    /// Returns the base sample time in seconds
+    fn sample_time() -> Option<f64> {
+        Self::info().sample_time
+    }
+    /// Returns the Simulink model information
+    fn info() -> ModelInfo;
+    /// Returns a snapshot of the states of the controller
+    fn snapshot(&self) -> Snapshot<Self>
+    where
+        Self: Sized,
+        Self::States: Clone,
+    {
+        Snapshot::new(self.states().clone())
+    }
+    /// Returns a snapshot of the states, inputs and outputs of the controller
+    fn snapshot_with_io(&self) -> Snapshot<Self>
+    where
+        Self: Sized,
+        Self::Inputs: Clone,
+        Self::Outputs: Clone,
+        Self::States: Clone,
+    {
+        Snapshot::new(self.states().clone())
+            .inputs(self.inputs().clone())
+            .outputs(self.outputs().clone())
+    }
+    /// Restores the states, and the inputs and outputs if any, from a snapshot
+    ///
+    /// Returns an error, leaving the controller unchanged, if the snapshot has been taken
+    /// with another model, with another interface or with another snapshot format
+    fn restore(&mut self, snapshot: &Snapshot<Self>) -> Result<(), SnapshotError>
+    where
+        Self: Sized,
+        Self::Inputs: Clone,
+        Self::Outputs: Clone,
+        Self::States: Clone,
+    {
+        snapshot.check()?;
+        self.states_mut().clone_from(&snapshot.states);
+        if let Some(inputs) = snapshot.inputs.as_ref() {
+            self.inputs_mut().clone_from(inputs);
+        }
+        if let Some(outputs) = snapshot.outputs.as_ref() {
+            self.outputs_mut().clone_from(outputs);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! Pre-generated bindings and controller module
+//!
+//! The bindings and the controller module can be written into a source directory of the crate
+//! (e.g. `src/generated`) and checked in, so that the crate can be built without libclang.
+//! Each generated file starts with the digest of the C header files and of the builder settings
+//! it has been generated from, the digest is checked against the current header files and
+//! settings when the pre-generated files are used.
+
+use simulink_model::fnv1a;
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+/// Prefix of the first line of the pre-generated files
+const DIGEST_PREFIX: &str = "// simulink-rs digest: ";
+
+/// Returns true if the pre-generated files must be used instead of running bindgen
+///
+/// The pre-generated files are used either if `simulink-rs` is built without the `bindgen`
+/// feature or if the crate that is being built has a `pregenerated` feature enabled
+pub(crate) fn is_offline() -> bool {
+    !cfg!(feature = "bindgen") || env::var_os("CARGO_FEATURE_PREGENERATED").is_some()
+}
+
+/// Computes the digest of the C header files, in any order, and of the builder settings
+pub(crate) fn digest(headers: &[PathBuf], settings: &[String]) -> String {
+    let mut headers: Vec<_> = headers
+        .iter()
+        .map(|header| {
+            let name = header
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+                .to_string();
+            let content = fs::read(header)
+                .unwrap_or_else(|e| panic!("failed to read header {:?}: {}", header, e));
+            (name, content)
+        })
+        .collect();
+    headers.sort();
+    format!(
+        "{:016x}",
+        fnv1a(
+            headers
+                .iter()
+                .flat_map(|(name, content)| [name.as_bytes(), content.as_slice()])
+                .chain(settings.iter().map(|setting| setting.as_bytes()))
+        )
+    )
+}
+
+/// Writes a pre-generated file into `dir` with the `digest` on the first line
+pub(crate) fn write(dir: &Path, file_name: &str, digest: &str, contents: &str) {
+    fs::create_dir_all(dir).unwrap_or_else(|e| panic!("failed to create {:?}: {}", dir, e));
+    let path = dir.join(file_name);
+    fs::write(&path, format!("{}{}\n{}", DIGEST_PREFIX, digest, contents))
+        .unwrap_or_else(|e| panic!("failed to write {:?}: {}", path, e));
+}
+
+/// Reads a pre-generated file from `dir`
+///
+/// Panics if the file is missing or if it has been generated from different header files
+/// or with different settings
+pub(crate) fn read(dir: &Path, file_name: &str, digest: &str) -> String {
+    let path = dir.join(file_name);
+    println!("cargo:rerun-if-changed={}", path.display());
+    let contents = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "failed to read pre-generated {:?} ({}), generate it with the `bindgen` feature of simulink-rs",
+            path, e
+        )
+    });
+    let found = contents
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix(DIGEST_PREFIX))
+        .unwrap_or_default();
+    if found != digest {
+        panic!(
+            "pre-generated {:?} is stale (digest: {:?}, expected {:?}), regenerate it with the `bindgen` feature of simulink-rs",
+            path, found, digest
+        );
+    }
+    contents
+}
@@ -0,0 +1,184 @@
+//! Extra code generation from the model description
+//!
+//! A [Generator] receives the [Model] parsed from the C files and returns Rust code that is
+//! written into `OUT_DIR`, next to the bindings and the controller module.
+//! A [Template] is a generator that renders a template file against the model.
+
+use simulink_model::{rust_ident, Model};
+use std::{
+    env,
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Generator of extra Rust code from the model description
+///
+/// # Example
+/// ```ignore
+/// struct SignalIds;
+/// impl Generator for SignalIds {
+///     fn file_name(&self) -> String {
+///         "signal_ids.rs".into()
+///     }
+///     fn generate(&self, model: &Model) -> String {
+///         todo!()
+///     }
+/// }
+/// let sys = Sys::builder().generator(SignalIds).build();
+/// ```
+/// and in the crate
+/// ```ignore
+/// include!(concat!(env!("OUT_DIR"), "/signal_ids.rs"));
+/// ```
+pub trait Generator {
+    /// Returns the name of the file written into `OUT_DIR`
+    fn file_name(&self) -> String;
+    /// Returns the Rust code generated from the model
+    fn generate(&self, model: &Model) -> String;
+}
+
+/// Generators registered with the [Builder](crate::Builder)
+#[derive(Default, Clone)]
+pub(crate) struct Generators(pub(crate) Vec<Arc<dyn Generator>>);
+impl Debug for Generators {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|generator| generator.file_name()))
+            .finish()
+    }
+}
+impl Generators {
+    /// Writes the code of each generator into `OUT_DIR`
+    pub(crate) fn write(&self, model: &Model) {
+        let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is not set"));
+        for generator in &self.0 {
+            let path = out_dir.join(generator.file_name());
+            fs::write(&path, generator.generate(model))
+                .unwrap_or_else(|e| panic!("failed to write {:?}: {}", path, e));
+        }
+    }
+}
+
+/// Template file rendered against the model description
+///
+/// The template is written into `OUT_DIR` with the name of the template file,
+/// without the `.tpl` extension if any, e.g. `templates/signals.rs.tpl` is written to `signals.rs`.
+///
+/// The placeholders are:
+///  * `{{model}}`: the model name,
+///  * `{{model_version}}`: the model version,
+///  * `{{sample_time}}`: the sample time in seconds,
+///  * `{{#inputs}}...{{/inputs}}`, `{{#outputs}}...{{/outputs}}`, `{{#states}}...{{/states}}`
+///    and `{{#parameters}}...{{/parameters}}`: repeated for each property of the structure,
+///    with the placeholders `{{name}}`, `{{rust_name}}`, `{{dtype}}`, `{{size}}` (1 for a scalar),
+///    `{{index}}` and `{{block_path}}`
+#[derive(Debug, Clone)]
+pub struct Template {
+    path: PathBuf,
+}
+impl Template {
+    /// Creates a new template from a file
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+    /// Renders a template against the model
+    pub fn render(template: &str, model: &Model) -> String {
+        let mut rendered = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("{{#") {
+            rendered.push_str(&substitute(&rest[..start], &|key| model_value(model, key)));
+            let section = &rest[start + 3..];
+            let Some(end) = section.find("}}") else {
+                panic!("unclosed section in template")
+            };
+            let name = &section[..end];
+            let body = &section[end + 2..];
+            let close = format!("{{{{/{}}}}}", name);
+            let Some(body_end) = body.find(&close) else {
+                panic!("missing {} in template", close)
+            };
+            let simulink = match name {
+                "inputs" => model.inputs(),
+                "outputs" => model.outputs(),
+                "states" => model.states(),
+                "parameters" => model.parameters(),
+                _ => panic!("unknown section {:?} in template", name),
+            };
+            for (index, io) in simulink
+                .into_iter()
+                .flat_map(|simulink| simulink.properties.iter())
+                .enumerate()
+            {
+                rendered.push_str(&substitute(&body[..body_end], &|key| match key {
+                    "name" => Some(io.name.clone()),
+                    "rust_name" => Some(rust_ident(&io.name).to_string()),
+                    "dtype" => Some(io.dtype.clone()),
+                    "size" => Some(io.size.unwrap_or(1).to_string()),
+                    "index" => Some(index.to_string()),
+                    "block_path" => Some(io.block_path.clone().unwrap_or_default()),
+                    _ => model_value(model, key),
+                }));
+            }
+            rest = &body[body_end + close.len()..];
+        }
+        rendered.push_str(&substitute(rest, &|key| model_value(model, key)));
+        rendered
+    }
+}
+impl Generator for Template {
+    fn file_name(&self) -> String {
+        let file_name = self
+            .path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_else(|| panic!("invalid template file {:?}", self.path));
+        file_name
+            .strip_suffix(".tpl")
+            .unwrap_or(file_name)
+            .to_string()
+    }
+    fn generate(&self, model: &Model) -> String {
+        println!("cargo:rerun-if-changed={}", self.path.display());
+        let template = fs::read_to_string(&self.path)
+            .unwrap_or_else(|e| panic!("failed to read template {:?}: {}", self.path, e));
+        Self::render(&template, model)
+    }
+}
+
+/// Returns the value of a model placeholder
+fn model_value(model: &Model, key: &str) -> Option<String> {
+    match key {
+        "model" => Some(model.name.clone()),
+        "model_version" => Some(model.metadata.model_version.clone().unwrap_or_default()),
+        "sample_time" => Some(
+            model
+                .sample_time
+                .map(|sample_time| format!("{:?}", sample_time))
+                .unwrap_or_default(),
+        ),
+        _ => None,
+    }
+}
+
+/// Replaces the `{{key}}` placeholders with their values
+fn substitute(text: &str, value: &dyn Fn(&str) -> Option<String>) -> String {
+    let mut substituted = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        substituted.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            panic!("unclosed placeholder in template")
+        };
+        let key = rest[start + 2..start + end].trim();
+        substituted.push_str(
+            &value(key).unwrap_or_else(|| panic!("unknown placeholder {:?} in template", key)),
+        );
+        rest = &rest[start + end + 2..];
+    }
+    substituted.push_str(rest);
+    substituted
+}
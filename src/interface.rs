@@ -0,0 +1,146 @@
+//! Simulink model interface lockfile
+//!
+//! The interface of a model, i.e. the names, types, sizes and order of its inputs, outputs,
+//! states and parameters, is written to a lockfile next to the sys folder.
+//! The lockfile is checked in, and the build fails if the C code is regenerated with
+//! a different interface, until the change is accepted by setting the
+//! `SIMULINK_RS_ACCEPT_INTERFACE` environment variable.
+
+use serde::{Deserialize, Serialize};
+use simulink_model::{Model, Simulink};
+use std::{fmt::Display, fs, io, path::Path};
+
+mod diff;
+pub use diff::{Change, Group};
+
+/// Environment variable that accepts the changes of the model interface
+pub const ACCEPT_INTERFACE: &str = "SIMULINK_RS_ACCEPT_INTERFACE";
+
+/// Interface lockfile mode
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InterfaceLock {
+    /// The lockfile is neither written nor checked
+    #[default]
+    Off,
+    /// The lockfile is written at each build
+    Update,
+    /// The build fails if the model interface does not match the lockfile
+    ///
+    /// The lockfile is written if it does not exist or if `SIMULINK_RS_ACCEPT_INTERFACE` is set
+    Check,
+}
+
+/// Simulink structure property as written to the lockfile
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Port {
+    pub name: String,
+    pub dtype: String,
+    pub size: Option<usize>,
+    /// Simulink block path, used to detect renamed ports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_path: Option<String>,
+}
+impl Display for Port {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.dtype)?;
+        if let Some(size) = self.size {
+            write!(f, "[{}]", size)?;
+        }
+        Ok(())
+    }
+}
+
+/// Simulink model interface
+///
+/// The ports are listed in the order of the C structures
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interface {
+    pub model: String,
+    /// Simulink model version, for information only, it is not compared nor hashed
+    pub model_version: Option<String>,
+    pub inputs: Vec<Port>,
+    pub outputs: Vec<Port>,
+    pub states: Vec<Port>,
+    pub parameters: Vec<Port>,
+}
+
+impl From<&Model> for Interface {
+    fn from(model: &Model) -> Self {
+        let ports = |simulink: Option<&Simulink>| {
+            simulink
+                .map(|simulink| {
+                    simulink
+                        .properties
+                        .iter()
+                        .map(|io| Port {
+                            name: io.name.clone(),
+                            dtype: io.dtype.clone(),
+                            size: io.size,
+                            block_path: io.block_path.clone(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            model: model.name.clone(),
+            model_version: model.metadata.model_version.clone(),
+            inputs: ports(model.inputs()),
+            outputs: ports(model.outputs()),
+            states: ports(model.states()),
+            parameters: ports(model.parameters()),
+        }
+    }
+}
+
+impl Interface {
+    /// Reads the interface from a lockfile
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    /// Writes the interface to a lockfile
+    ///
+    /// The lockfile is left untouched if its content is already the interface,
+    /// so that the build scripts watching the lockfile are not re-run
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+            + "\n";
+        if fs::read_to_string(&path).is_ok_and(|locked| locked == contents) {
+            return Ok(());
+        }
+        fs::write(path, contents)
+    }
+    /// Returns the ports of the inputs, outputs, states and parameters
+    fn groups(&self) -> [(Group, &[Port]); 4] {
+        [
+            (Group::Inputs, &self.inputs),
+            (Group::Outputs, &self.outputs),
+            (Group::States, &self.states),
+            (Group::Parameters, &self.parameters),
+        ]
+    }
+    /// Checks if two interfaces have the same model name and ports
+    pub fn matches(&self, other: &Interface) -> bool {
+        self.diff(other).is_empty()
+    }
+    /// Computes the 64 bits hash of the interface
+    ///
+    /// The hash depends on the model name and on the names, types, sizes and order of the ports,
+    /// but not on the model version nor on the block paths,
+    /// it is the same as [Model::interface_hash]
+    pub fn hash(&self) -> u64 {
+        simulink_model::interface_hash(
+            &self.model,
+            self.groups().map(|(group, ports)| {
+                (
+                    group.as_str(),
+                    ports
+                        .iter()
+                        .map(|port| (port.name.as_str(), port.dtype.as_str(), port.size)),
+                )
+            }),
+        )
+    }
+}
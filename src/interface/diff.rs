@@ -0,0 +1,229 @@
+use serde::Serialize;
+use std::fmt::Display;
+
+use super::{Interface, Port};
+
+/// Simulink structures of the model interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Group {
+    Inputs,
+    Outputs,
+    States,
+    Parameters,
+}
+impl Group {
+    /// Returns the name of the group
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Group::Inputs => "inputs",
+            Group::Outputs => "outputs",
+            Group::States => "states",
+            Group::Parameters => "parameters",
+        }
+    }
+}
+impl Display for Group {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Change of the model interface
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum Change {
+    /// The model has been renamed
+    ModelRenamed { from: String, to: String },
+    /// A port has been added
+    Added { group: Group, port: Port },
+    /// A port has been removed
+    Removed { group: Group, port: Port },
+    /// A port has been renamed, the old and new ports have the same block path
+    Renamed {
+        group: Group,
+        from: String,
+        to: String,
+        block_path: String,
+    },
+    /// The size of a port has changed, `None` is a scalar
+    Resized {
+        group: Group,
+        name: String,
+        from: Option<usize>,
+        to: Option<usize>,
+    },
+    /// The data type of a port has changed
+    DtypeChanged {
+        group: Group,
+        name: String,
+        from: String,
+        to: String,
+    },
+    /// The order of the ports has changed
+    Reordered { group: Group },
+}
+impl Change {
+    /// Returns the group of the ports that have changed
+    pub fn group(&self) -> Option<Group> {
+        match self {
+            Change::ModelRenamed { .. } => None,
+            Change::Added { group, .. }
+            | Change::Removed { group, .. }
+            | Change::Renamed { group, .. }
+            | Change::Resized { group, .. }
+            | Change::DtypeChanged { group, .. }
+            | Change::Reordered { group } => Some(*group),
+        }
+    }
+    /// Checks if the change breaks the Rust API of the model crate
+    ///
+    /// Any change of the inputs and outputs is breaking, including their order that sets
+    /// the layout of the flattened slices and of the wire format,
+    /// so is any change of the states, that breaks the saved snapshots.
+    /// The parameters types are part of the bindings and are mapped from MAT-files by field name,
+    /// so only adding a parameter is not breaking
+    pub fn is_breaking(&self) -> bool {
+        !matches!(
+            self,
+            Change::Added {
+                group: Group::Parameters,
+                ..
+            }
+        )
+    }
+}
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let size = |size: &Option<usize>| {
+            size.map(|size| size.to_string())
+                .unwrap_or_else(|| "scalar".to_string())
+        };
+        match self {
+            Change::ModelRenamed { from, to } => write!(f, "model renamed from {} to {}", from, to),
+            Change::Added { group, port } => write!(f, "{}: added {}", group, port),
+            Change::Removed { group, port } => write!(f, "{}: removed {}", group, port),
+            Change::Renamed {
+                group,
+                from,
+                to,
+                block_path,
+            } => write!(f, "{}: {} renamed to {} ({})", group, from, to, block_path),
+            Change::Resized {
+                group,
+                name,
+                from,
+                to,
+            } => write!(
+                f,
+                "{}: {} resized from {} to {}",
+                group,
+                name,
+                size(from),
+                size(to)
+            ),
+            Change::DtypeChanged {
+                group,
+                name,
+                from,
+                to,
+            } => write!(
+                f,
+                "{}: {} type changed from {} to {}",
+                group, name, from, to
+            ),
+            Change::Reordered { group } => write!(f, "{}: reordered", group),
+        }
+    }
+}
+
+impl Interface {
+    /// Returns the changes of the interface from `self` to `new`
+    ///
+    /// Ports are matched by name, a removed port and an added port with the same block path
+    /// are reported as a renamed port.
+    /// The model version and the block paths of the matched ports are not compared
+    pub fn diff(&self, new: &Interface) -> Vec<Change> {
+        let mut changes = vec![];
+        if self.model != new.model {
+            changes.push(Change::ModelRenamed {
+                from: self.model.clone(),
+                to: new.model.clone(),
+            });
+        }
+        for ((group, old_ports), (_, new_ports)) in self.groups().into_iter().zip(new.groups()) {
+            diff_ports(group, old_ports, new_ports, &mut changes);
+        }
+        changes
+    }
+}
+
+/// Compares the ports of a Simulink structure
+fn diff_ports(group: Group, old: &[Port], new: &[Port], changes: &mut Vec<Change>) {
+    let find = |ports: &[Port], name: &str| ports.iter().position(|port| port.name == name);
+    // pairs of matching ports, as indices into the old and new ports
+    let mut pairs = vec![];
+    let mut removed = vec![];
+    for (i, port) in old.iter().enumerate() {
+        match find(new, &port.name) {
+            Some(j) => pairs.push((i, j)),
+            None => removed.push(i),
+        }
+    }
+    let mut added: Vec<_> = (0..new.len())
+        .filter(|&j| find(old, &new[j].name).is_none())
+        .collect();
+
+    for i in removed {
+        let renamed = old[i].block_path.as_ref().and_then(|block_path| {
+            added
+                .iter()
+                .position(|&j| new[j].block_path.as_ref() == Some(block_path))
+        });
+        match renamed {
+            Some(k) => {
+                let j = added.remove(k);
+                changes.push(Change::Renamed {
+                    group,
+                    from: old[i].name.clone(),
+                    to: new[j].name.clone(),
+                    block_path: new[j].block_path.clone().unwrap_or_default(),
+                });
+                pairs.push((i, j));
+            }
+            None => changes.push(Change::Removed {
+                group,
+                port: old[i].clone(),
+            }),
+        }
+    }
+    changes.extend(added.into_iter().map(|j| Change::Added {
+        group,
+        port: new[j].clone(),
+    }));
+
+    pairs.sort();
+    for &(i, j) in &pairs {
+        let (old, new) = (&old[i], &new[j]);
+        if old.size != new.size {
+            changes.push(Change::Resized {
+                group,
+                name: new.name.clone(),
+                from: old.size,
+                to: new.size,
+            });
+        }
+        if old.dtype != new.dtype {
+            changes.push(Change::DtypeChanged {
+                group,
+                name: new.name.clone(),
+                from: old.dtype.clone(),
+                to: new.dtype.clone(),
+            });
+        }
+    }
+    // the ports are in the old order, they are reordered if the new indices are not increasing
+    if pairs.windows(2).any(|pair| pair[0].1 > pair[1].1) {
+        changes.push(Change::Reordered { group });
+    }
+}
@@ -7,18 +7,65 @@
 //! let sys = Sys::new(Some("MySimulinkController"));
 //! sys.compile().generate_module();
 //! ```
+//!
+//! # Offline mode
+//!
+//! With [Builder::generated_dir], the bindings and the controller module are also written
+//! into a source directory of the crate that can be checked in.
+//! If `simulink-rs` is built without the default `bindgen` feature, or if the crate has a
+//! `pregenerated` feature that is enabled, bindgen is skipped and the pre-generated files are
+//! used instead, so libclang is not required.
+//! The build fails if the pre-generated files are stale relative to the C header files,
+//! to the builder settings or to the version of `simulink-rs`.
 
-use regex::Regex;
+use quote::ToTokens;
 use std::{
     env,
     fmt::{Debug, Display},
-    fs::{self, File},
-    io::{BufRead, BufReader},
+    fs,
     path::{Path, PathBuf},
 };
 
-mod model;
-use model::{Model, Simulink};
+mod allowlist;
+pub use allowlist::Allowlist;
+mod builder;
+pub use builder::Builder;
+mod compiler;
+pub use compiler::{Compiler, OptLevel, Sanitizer};
+mod controller;
+pub use controller::{Flatten, ModelInfo, SimulinkController};
+mod generated;
+mod generator;
+pub use generator::{Generator, Template};
+mod interface;
+pub use interface::{Change, Group, Interface, InterfaceLock, Port, ACCEPT_INTERFACE};
+#[cfg(feature = "mat")]
+pub mod mat;
+pub mod npy;
+mod patch;
+pub use patch::{Partial, Patch};
+mod seed;
+pub use seed::ArraySeed;
+mod signal;
+pub use signal::{Direction, SignalError, SignalInfo};
+pub mod simulation;
+mod snapshot;
+pub use snapshot::{Snapshot, SnapshotError, SNAPSHOT_FORMAT};
+pub mod testing;
+pub mod trace;
+mod wire;
+pub use wire::{Wire, WireError};
+
+pub use simulink_macros::include_model;
+use simulink_model::{Model, Options};
+
+/// Simulink model description
+///
+/// The model parsed from the C header and source files is returned by [Sys::model],
+/// it can be serialized for custom code generation in build scripts
+pub mod model {
+    pub use simulink_model::{pretty, rust_ident, List, Metadata, Model, Simulink, Symbols, IO};
+}
 
 /// Simulink control system C source and header files parser and builder
 ///
@@ -30,69 +77,17 @@ use model::{Model, Simulink};
 #[derive(Debug, Default, Clone)]
 pub struct Sys {
     controller: Option<String>,
+    folder: PathBuf,
     sources: Vec<PathBuf>,
     headers: Vec<PathBuf>,
-}
-
-pub struct Builder {
-    controller_type: Option<String>,
-    sys_folder: String,
-}
-impl Default for Builder {
-    fn default() -> Self {
-        Self {
-            controller_type: Default::default(),
-            sys_folder: "sys".into(),
-        }
-    }
-}
-impl Builder {
-    /// Sets the name of the Rust structure that acts as a wrapper for the Simulink C code
-    ///
-    /// If not set, the structure is given the same name than the Simulink control model
-    pub fn name<S: Into<String>>(mut self, rs_type: S) -> Self {
-        self.controller_type = Some(rs_type.into());
-        self
-    }
-    /// Sets the name of the folder with the C header and source file
-    ///
-    /// If not set, expect the folder to be named "sys"
-    pub fn folder<S: Into<String>>(mut self, folder: S) -> Self {
-        self.sys_folder = folder.into();
-        self
-    }
-    /// Builds a new Simulink C to Rust wrapper
-    pub fn build(self) -> Sys {
-        let sys = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(self.sys_folder);
-
-        let mut sources = vec![];
-        let mut headers = vec![];
-
-        if let Ok(entries) = fs::read_dir(&sys) {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let file_name = entry.path();
-                    if let Some(extension) = file_name.extension() {
-                        match extension.to_str() {
-                            Some("c") => {
-                                sources.push(file_name);
-                            }
-                            Some("h") => {
-                                headers.push(file_name);
-                            }
-                            _ => (),
-                        }
-                    }
-                }
-            }
-        }
-
-        Sys {
-            controller: self.controller_type,
-            sources,
-            headers,
-        }
-    }
+    include_dirs: Vec<PathBuf>,
+    compiler: Compiler,
+    generated_dir: Option<PathBuf>,
+    allowlist: Allowlist,
+    example_mains: Vec<PathBuf>,
+    lock: InterfaceLock,
+    lockfile: PathBuf,
+    generators: generator::Generators,
 }
 
 impl Sys {
@@ -100,131 +95,273 @@ impl Sys {
     ///
     /// The Simulink controlller will be given the type `rs_type` if present
     pub fn new<S: Into<String>>(rs_type: Option<S>) -> Self {
-        let sys = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("sys");
-
-        let mut sources = vec![];
-        let mut headers = vec![];
-
-        if let Ok(entries) = fs::read_dir(&sys) {
-            for entry in entries {
-                if let Ok(entry) = entry {
-                    let file_name = entry.path();
-                    if let Some(extension) = file_name.extension() {
-                        match extension.to_str() {
-                            Some("c") => {
-                                sources.push(file_name);
-                            }
-                            Some("h") => {
-                                headers.push(file_name);
-                            }
-                            _ => (),
-                        }
-                    }
-                }
-            }
-        }
-
-        Self {
-            controller: rs_type.map(|x| x.into()),
-            sources,
-            headers,
+        match rs_type {
+            Some(rs_type) => Self::builder().name(rs_type),
+            None => Self::builder(),
         }
+        .build()
     }
     /// Creates a builder for the Simulink C to Rust wrapper
     pub fn builder() -> Builder {
         Default::default()
     }
+    /// Returns the C source files
+    pub fn sources(&self) -> &[PathBuf] {
+        &self.sources
+    }
+    /// Returns the C header files
+    pub fn headers(&self) -> &[PathBuf] {
+        &self.headers
+    }
+    /// Returns the C include directories
+    pub fn include_dirs(&self) -> &[PathBuf] {
+        &self.include_dirs
+    }
+    /// Returns the C compiler configuration
+    pub fn compiler(&self) -> &Compiler {
+        &self.compiler
+    }
     /// Returns the main header file
-    fn header(&self) -> Option<&str> {
-        self.headers.iter().find_map(|header| {
-            header.to_str().filter(|f| {
-                !(f.ends_with("rtwtypes.h")
-                    || f.ends_with("rt_defines.h")
-                    || f.ends_with("_private.h")
-                    || f.ends_with("_types.h"))
-            })
-        })
+    fn header(&self) -> Option<&Path> {
+        simulink_model::main_header(&self.headers, &self.sources)
     }
-    /// Parses the main header file into [Model]
+    /// Parses the C header and source files into [Model]
     ///
-    /// Extract the model name and the lists of inputs, outputs and states variables
-    /// and creates a [Model]
-    fn parse_header(&self) -> Model {
+    /// Extract the model name, the lists of inputs, outputs, states and parameters variables,
+    /// the C symbols, the metadata and the sample time, and creates a [Model]
+    pub fn model(&self) -> Model {
         let Some(header) = self.header() else {
-            panic!("cannot find error in sys")
-        };
-        let file = File::open(header).expect(&format!("file {:?} not found", header));
-        let reader = BufReader::new(file);
-        let mut lines = reader.lines();
-
-        let mut model = Model::default();
-        model.name = loop {
-            if let Some(Ok(line)) = lines.next() {
-                if line.contains("File:") {
-                    let regex = Regex::new(r"File:\s*(\w+)\.h").unwrap();
-                    if let Some(captures) = regex.captures(&line) {
-                        let name = captures.get(1).unwrap().as_str();
-                        break name.to_string();
-                    }
-                }
-            }
+            panic!("cannot find the main header file in sys")
         };
-        while let Some(data) = Simulink::parse_io(&mut lines) {
-            model.simulink.push(data);
-        }
-        model
+        let sources: Vec<_> = self
+            .sources
+            .iter()
+            .chain(&self.example_mains)
+            .cloned()
+            .collect();
+        Model::from_files(header, &self.headers, &sources)
+            .unwrap_or_else(|e| panic!("failed to parse {:?}: {}", header, e))
     }
     /// Compiles the Simulink C model
+    ///
+    /// The model interface is checked against the lockfile first, see [Sys::lock_interface],
+    /// and the code of the [Generator]s is written into `OUT_DIR` last, see [Sys::generate].
+    /// The C files are passed on to the compilation of the crate, so that
+    /// [include_model] parses the same files
     pub fn compile(&self) -> &Self {
+        self.lock_interface();
+        let sources: Vec<_> = self
+            .sources
+            .iter()
+            .chain(&self.example_mains)
+            .cloned()
+            .collect();
+        simulink_model::export_files(&self.folder, &self.headers, &sources);
         let mut cc_builder = cc::Build::new();
         self.sources
             .iter()
             .fold(&mut cc_builder, |cc_builder, source| {
+                println!("cargo:rerun-if-changed={:}", source.to_str().unwrap());
                 cc_builder.file(source)
             });
+        cc_builder.includes(&self.include_dirs);
+        self.compiler.configure(&mut cc_builder);
+
+        let lib = env::var("CARGO_PKG_NAME").unwrap();
+        println!("cargo:rustc-link-search=native=lib{}", lib);
+        println!("cargo:rustc-link-lib={}", lib);
+
+        cc_builder.compile(lib.as_str());
+
+        // the pre-generated files are checked against the headers, online or offline
+        for header in &self.headers {
+            println!("cargo:rerun-if-changed={}", header.display());
+        }
+        let bindings = if generated::is_offline() {
+            generated::read(&self.generated_dir(), "bindings.rs", &self.digest())
+        } else {
+            let bindings = self.bindings();
+            if let Some(generated_dir) = self.generated_dir.as_ref() {
+                generated::write(generated_dir, "bindings.rs", &self.digest(), &bindings);
+            }
+            bindings
+        };
+        let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+        fs::write(out_path.join("bindings.rs"), bindings).expect("Couldn't write bindings!");
+        self.generate();
+        self
+    }
+    /// Generates the Rust bindings to the C header files
+    #[cfg(feature = "bindgen")]
+    fn bindings(&self) -> String {
+        let bindings_builder = self.allowlist().apply(bindgen::builder());
         let bindings_builder = self
             .headers
             .iter()
-            .fold(bindgen::builder(), |bindings, header| {
-                println!("cargo:rerun-if-changed={:}", header.to_str().unwrap());
+            .fold(bindings_builder, |bindings, header| {
                 bindings.header(
                     header
                         .to_str()
-                        .expect(&format!("{:?} conversion to str failed", header)),
+                        .unwrap_or_else(|| panic!("{:?} conversion to str failed", header)),
                 )
-            });
-
-        let lib = env::var("CARGO_PKG_NAME").unwrap();
-        println!("cargo:rustc-link-search=native=lib{}", lib);
-        println!("cargo:rustc-link-lib={}", lib);
-
-        cc_builder.compile(lib.as_str());
-        let bindings = bindings_builder
+            })
+            .clang_args(
+                self.include_dirs
+                    .iter()
+                    .map(|dir| format!("-I{}", dir.display())),
+            )
+            .clang_args(self.compiler.clang_args());
+        bindings_builder
             .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
             .generate()
-            .expect("Unable to generate bindings");
-        let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
-        bindings
-            .write_to_file(out_path.join("bindings.rs"))
-            .expect("Couldn't write bindings!");
+            .expect("Unable to generate bindings")
+            .to_string()
+    }
+    #[cfg(not(feature = "bindgen"))]
+    fn bindings(&self) -> String {
+        unreachable!("bindings are pre-generated without the bindgen feature")
+    }
+    /// Returns the bindgen allowlist
+    ///
+    /// The allowlist is made of the model inputs, outputs, states and parameters types,
+    /// the real-time model structure, the entry point functions, the enum and bus typedefs,
+    /// and of the patterns set with the [Builder]
+    pub fn allowlist(&self) -> Allowlist {
+        self.allowlist.resolve(&self.model())
+    }
+    /// Writes the code of the [Generator]s registered with the [Builder] into `OUT_DIR`
+    pub fn generate(&self) -> &Self {
+        if !self.generators.0.is_empty() {
+            self.generators.write(&self.model());
+        }
         self
     }
+    /// Returns the interface of the model
+    pub fn interface(&self) -> Interface {
+        Interface::from(&self.model())
+    }
+    /// Returns the path to the interface lockfile
+    pub fn lockfile(&self) -> &Path {
+        &self.lockfile
+    }
+    /// Writes or checks the interface lockfile according to the [InterfaceLock] mode
+    ///
+    /// Panics in [InterfaceLock::Check] mode if the model interface does not match the lockfile,
+    /// unless the `SIMULINK_RS_ACCEPT_INTERFACE` environment variable is set
+    pub fn lock_interface(&self) {
+        if self.lock == InterfaceLock::Off {
+            return;
+        }
+        println!("cargo:rerun-if-changed={}", self.lockfile.display());
+        println!("cargo:rerun-if-env-changed={}", ACCEPT_INTERFACE);
+        let interface = self.interface();
+        let write = |interface: &Interface| {
+            interface
+                .write(&self.lockfile)
+                .unwrap_or_else(|e| panic!("failed to write {:?}: {}", self.lockfile, e))
+        };
+        if self.lock == InterfaceLock::Update {
+            write(&interface);
+            return;
+        }
+        if !self.lockfile.exists() {
+            println!(
+                "cargo:warning=writing the interface lockfile {:?}",
+                self.lockfile
+            );
+            write(&interface);
+            return;
+        }
+        let locked = Interface::read(&self.lockfile)
+            .unwrap_or_else(|e| panic!("failed to read {:?}: {}", self.lockfile, e));
+        let changes = locked.diff(&interface);
+        if changes.is_empty() {
+            if locked != interface {
+                // only the model version or the block paths have changed
+                write(&interface);
+            }
+            return;
+        }
+        if env::var_os(ACCEPT_INTERFACE).is_some() {
+            println!(
+                "cargo:warning=accepting the new interface of model {} into {:?}",
+                interface.model, self.lockfile
+            );
+            write(&interface);
+            return;
+        }
+        panic!(
+            "the interface of model {} does not match {:?}:\n{}\nset {}=1 to accept the changes",
+            interface.model,
+            self.lockfile,
+            changes
+                .iter()
+                .map(|change| format!(
+                    "  {} ({})",
+                    change,
+                    if change.is_breaking() {
+                        "breaking"
+                    } else {
+                        "non-breaking"
+                    }
+                ))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ACCEPT_INTERFACE
+        );
+    }
+    /// Returns the digest of the C header files and of the builder settings
+    fn digest(&self) -> String {
+        // the settings of the builder that the bindings and the controller module depend on
+        let settings = [
+            env!("CARGO_PKG_VERSION").to_string(),
+            format!("{:?}", self.controller),
+            format!("{:?}", self.allowlist),
+            format!("{:?}", self.compiler.clang_args()),
+        ];
+        generated::digest(&self.headers, &settings)
+    }
+    /// Returns the directory of the pre-generated bindings and controller module
+    ///
+    /// If not set, the directory defaults to `src/generated`
+    pub fn generated_dir(&self) -> PathBuf {
+        self.generated_dir.clone().unwrap_or_else(|| {
+            Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+                .join("src")
+                .join("generated")
+        })
+    }
     /// Generates the controller.rs module
+    ///
+    /// If a directory for pre-generated files is set, the module is also written into it
     pub fn generate_module(&self) {
+        let controller = if generated::is_offline() {
+            generated::read(&self.generated_dir(), "controller.rs", &self.digest())
+        } else {
+            let controller = self.to_string();
+            if let Some(generated_dir) = self.generated_dir.as_ref() {
+                generated::write(generated_dir, "controller.rs", &self.digest(), &controller);
+            }
+            controller
+        };
         let out_dir = env::var_os("OUT_DIR").unwrap();
         let dest_path = Path::new(&out_dir).join("controller.rs");
-        fs::write(&dest_path, format!("{}", self)).unwrap();
+        fs::write(&dest_path, controller).unwrap();
+    }
+}
+
+impl ToTokens for Sys {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let options = Options {
+            rs_type: self.controller.clone(),
+            ..Default::default()
+        };
+        tokens.extend(self.model().wrapper(&options));
     }
 }
 
 impl Display for Sys {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let model = self.parse_header();
-        if let Some(controller) = self.controller.as_ref() {
-            writeln!(f, "/// Rust binder to Simulink C controller wrapper")?;
-            writeln!(f, "#[allow(dead_code)]")?;
-            writeln!(f, "pub type {} = {};", controller, model.name)?;
-        }
-        <Model as Display>::fmt(&model, f)
+        write!(f, "{}", simulink_model::pretty(self.to_token_stream()))
     }
 }
@@ -0,0 +1,242 @@
+//! MATLAB MAT-file (version 5) reader and writer
+//!
+//! The MAT-files saved by MATLAB with the default `-v7` option, or with `-v6`, are read,
+//! compressed or not, and MAT-files are written uncompressed in the version 5 format.
+//! Numeric, logical and char arrays, structures and cell arrays are supported,
+//! sparse arrays and objects are not.
+//!
+//! The MATLAB structures are mapped onto the Simulink structures of the controllers,
+//! e.g. the inputs or the parameters, by field name with [MatFile::deserialize],
+//! the fields of the MATLAB structure that are not fields of the Simulink structure are ignored.
+//! Conversely, a Simulink structure is saved as a MATLAB structure with [MatFile::serialize].
+//!
+//! # Example
+//! ```ignore
+//! use simulink_rs::mat::MatFile;
+//!
+//! let mat = MatFile::read("parameters.mat")?;
+//! let inputs: ExtU_M1SA_Control_OA_T = mat.deserialize("inputs")?;
+//!
+//! let mut mat = MatFile::default();
+//! mat.serialize("outputs", &controller.outputs)?;
+//! mat.write("outputs.mat")?;
+//! ```
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    error::Error,
+    fmt::Display,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+mod de;
+mod read;
+mod ser;
+mod write;
+
+/// Class of a MATLAB numeric array
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatClass {
+    Double,
+    Single,
+    Int8,
+    Uint8,
+    Int16,
+    Uint16,
+    Int32,
+    Uint32,
+    Int64,
+    Uint64,
+    /// Logical array, saved as a `uint8` array
+    Logical,
+}
+
+/// Data of a MATLAB array
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatData {
+    /// Numeric or logical array, in column-major order
+    ///
+    /// The values are converted to `f64`, so 64 bits integers beyond 2^53 lose precision
+    Numeric {
+        class: MatClass,
+        real: Vec<f64>,
+        imag: Option<Vec<f64>>,
+    },
+    /// Char array, the rows of a char matrix are separated with new lines
+    Char(String),
+    /// Structure array
+    ///
+    /// Each element of the structure array, in column-major order, has one array per field
+    Struct {
+        fields: Vec<String>,
+        elements: Vec<Vec<MatArray>>,
+    },
+    /// Cell array, in column-major order
+    Cell(Vec<MatArray>),
+}
+
+/// MATLAB array
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatArray {
+    /// Variable name, empty for the fields of structures and the cells
+    pub name: String,
+    /// Dimensions
+    pub dims: Vec<usize>,
+    /// Data
+    pub data: MatData,
+}
+impl MatArray {
+    /// Creates a numeric array of class `double`
+    pub fn double<S: Into<String>>(name: S, dims: Vec<usize>, real: Vec<f64>) -> Self {
+        Self {
+            name: name.into(),
+            dims,
+            data: MatData::Numeric {
+                class: MatClass::Double,
+                real,
+                imag: None,
+            },
+        }
+    }
+    /// Returns the number of elements, saturated at `usize::MAX`
+    pub fn len(&self) -> usize {
+        self.dims
+            .iter()
+            .fold(1usize, |n, &dim| n.saturating_mul(dim))
+    }
+    /// Checks if the array is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the real values of a numeric or logical array
+    pub fn real(&self) -> Option<&[f64]> {
+        match &self.data {
+            MatData::Numeric { real, .. } => Some(real),
+            _ => None,
+        }
+    }
+    /// Returns the field of a scalar structure
+    pub fn field(&self, name: &str) -> Option<&MatArray> {
+        match &self.data {
+            MatData::Struct { fields, elements } if elements.len() == 1 => fields
+                .iter()
+                .position(|field| field == name)
+                .map(|i| &elements[0][i]),
+            _ => None,
+        }
+    }
+    /// Maps the array onto a Rust type, e.g. a MATLAB structure onto a Simulink structure
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, MatError> {
+        T::deserialize(de::ArrayDeserializer(self))
+    }
+    /// Creates an array from a Rust type, e.g. a MATLAB structure from a Simulink structure
+    pub fn serialize<S: Into<String>, T: Serialize>(name: S, value: &T) -> Result<Self, MatError> {
+        let mut array = value.serialize(ser::ArraySerializer)?;
+        array.name = name.into();
+        Ok(array)
+    }
+}
+
+/// MATLAB MAT-file
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MatFile {
+    /// Variables
+    pub arrays: Vec<MatArray>,
+}
+impl MatFile {
+    /// Reads a MAT-file
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, MatError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+    /// Reads a MAT-file from a reader
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, MatError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        read::mat_file(&bytes)
+    }
+    /// Writes the MAT-file
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), MatError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.to_writer(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+    /// Writes the MAT-file into a writer
+    pub fn to_writer<W: Write>(&self, mut writer: W) -> Result<(), MatError> {
+        writer.write_all(&write::mat_file(self)?)?;
+        Ok(())
+    }
+    /// Returns the variable of a given name
+    pub fn get(&self, name: &str) -> Option<&MatArray> {
+        self.arrays.iter().find(|array| array.name == name)
+    }
+    /// Adds a variable, replacing the variable of the same name if any
+    pub fn insert(&mut self, array: MatArray) {
+        match self.arrays.iter_mut().find(|a| a.name == array.name) {
+            Some(a) => *a = array,
+            None => self.arrays.push(array),
+        }
+    }
+    /// Maps a variable onto a Rust type, e.g. a MATLAB structure onto a Simulink structure
+    pub fn deserialize<T: DeserializeOwned>(&self, name: &str) -> Result<T, MatError> {
+        self.get(name)
+            .ok_or_else(|| MatError::NotFound(name.to_string()))?
+            .deserialize()
+    }
+    /// Adds a variable from a Rust type, e.g. a MATLAB structure from a Simulink structure
+    pub fn serialize<T: Serialize>(&mut self, name: &str, value: &T) -> Result<(), MatError> {
+        self.insert(MatArray::serialize(name, value)?);
+        Ok(())
+    }
+}
+
+/// Error of the MAT-file reader and writer
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MatError {
+    Io(io::Error),
+    /// The file is not a valid MAT-file
+    Format(String),
+    /// The MAT-file uses a feature that is not supported, e.g. sparse arrays
+    Unsupported(String),
+    /// The MAT-file has no variable of that name
+    NotFound(String),
+    /// The array cannot be converted from or to the Rust type
+    Serde(String),
+}
+impl Display for MatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatError::Io(e) => e.fmt(f),
+            MatError::Format(msg) => write!(f, "invalid MAT-file: {}", msg),
+            MatError::Unsupported(msg) => write!(f, "unsupported MAT-file {}", msg),
+            MatError::NotFound(name) => write!(f, "variable {} not found in MAT-file", name),
+            MatError::Serde(msg) => msg.fmt(f),
+        }
+    }
+}
+impl Error for MatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MatError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+impl From<io::Error> for MatError {
+    fn from(e: io::Error) -> Self {
+        MatError::Io(e)
+    }
+}
+impl serde::de::Error for MatError {
+    fn custom<T: Display>(msg: T) -> Self {
+        MatError::Serde(msg.to_string())
+    }
+}
+impl serde::ser::Error for MatError {
+    fn custom<T: Display>(msg: T) -> Self {
+        MatError::Serde(msg.to_string())
+    }
+}
@@ -0,0 +1,301 @@
+//! Mapping of the MATLAB arrays onto Rust types
+
+use super::{MatArray, MatData, MatError};
+use serde::{
+    de::{DeserializeSeed, Error, IntoDeserializer, MapAccess, SeqAccess, Unexpected, Visitor},
+    forward_to_deserialize_any, Deserializer,
+};
+
+/// Element of a numeric array
+struct Scalar(f64);
+
+macro_rules! integer {
+    ($($deserialize:ident $visit:ident $ty:ty),*) => {
+        $(
+            fn $deserialize<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+                let value = self.0 as $ty;
+                if value as f64 != self.0 {
+                    return Err(Error::invalid_value(Unexpected::Float(self.0), &visitor));
+                }
+                visitor.$visit(value)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Scalar {
+    type Error = MatError;
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+        visitor.visit_f64(self.0)
+    }
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+        visitor.visit_bool(self.0 != 0.)
+    }
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+        visitor.visit_f32(self.0 as f32)
+    }
+    integer!(
+        deserialize_i8 visit_i8 i8,
+        deserialize_u8 visit_u8 u8,
+        deserialize_i16 visit_i16 i16,
+        deserialize_u16 visit_u16 u16,
+        deserialize_i32 visit_i32 i32,
+        deserialize_u32 visit_u32 u32,
+        deserialize_i64 visit_i64 i64,
+        deserialize_u64 visit_u64 u64
+    );
+    forward_to_deserialize_any! {
+        f64 char str string bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any i128 u128
+    }
+}
+
+/// Sequence of deserializers
+struct Seq<I>(I);
+impl<'de, I, D> SeqAccess<'de> for Seq<I>
+where
+    I: Iterator<Item = D>,
+    D: Deserializer<'de, Error = MatError>,
+{
+    type Error = MatError;
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, MatError> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(value))
+            .transpose()
+    }
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint().1
+    }
+}
+
+/// Fields of a structure
+struct Fields<'a, I> {
+    fields: I,
+    value: Option<&'a MatArray>,
+}
+impl<'de, 'a, I> MapAccess<'de> for Fields<'a, I>
+where
+    I: Iterator<Item = (&'a String, &'a MatArray)>,
+{
+    type Error = MatError;
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, MatError> {
+        let Some((field, value)) = self.fields.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(field.as_str().into_deserializer())
+            .map(Some)
+    }
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, MatError> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| MatError::Serde("value requested before key".into()))?;
+        seed.deserialize(ArrayDeserializer(value))
+    }
+}
+
+/// Element of a structure array
+struct Element<'a> {
+    fields: &'a [String],
+    values: &'a [MatArray],
+}
+impl<'a> Element<'a> {
+    /// Visits the fields of the element, or only the given fields
+    fn visit<'de, V: Visitor<'de>>(
+        self,
+        only: Option<&'static [&'static str]>,
+        visitor: V,
+    ) -> Result<V::Value, MatError> {
+        visitor.visit_map(Fields {
+            fields: self
+                .fields
+                .iter()
+                .zip(self.values)
+                .filter(|(field, _)| only.map_or(true, |only| only.contains(&field.as_str()))),
+            value: None,
+        })
+    }
+}
+impl<'de, 'a> Deserializer<'de> for Element<'a> {
+    type Error = MatError;
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+        self.visit(None, visitor)
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, MatError> {
+        // the fields that are not in the Rust type are left out
+        self.visit(Some(fields), visitor)
+    }
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+/// Deserializer of a MATLAB array
+///
+/// A numeric array is either a number, if it has a single element, or a sequence of numbers,
+/// a char array is a string, a scalar structure is a map, a structure array is a sequence
+/// of maps and a cell array is a sequence
+pub(super) struct ArrayDeserializer<'a>(pub(super) &'a MatArray);
+
+impl<'a> ArrayDeserializer<'a> {
+    /// Returns the single element of a numeric array
+    fn scalar(&self) -> Result<Scalar, MatError> {
+        match &self.0.data {
+            MatData::Numeric { real, .. } if real.len() == 1 => Ok(Scalar(real[0])),
+            MatData::Numeric { real, .. } => {
+                Err(Error::invalid_length(real.len(), &"a numeric scalar"))
+            }
+            _ => Err(Error::invalid_type(self.unexpected(), &"a numeric scalar")),
+        }
+    }
+    fn unexpected(&self) -> Unexpected<'a> {
+        match &self.0.data {
+            MatData::Numeric { .. } => Unexpected::Other("numeric array"),
+            MatData::Char(text) => Unexpected::Str(text),
+            MatData::Struct { .. } => Unexpected::Other("structure"),
+            MatData::Cell(_) => Unexpected::Other("cell array"),
+        }
+    }
+}
+
+macro_rules! scalar {
+    ($($deserialize:ident),*) => {
+        $(
+            fn $deserialize<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+                self.scalar()?.$deserialize(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> Deserializer<'de> for ArrayDeserializer<'a> {
+    type Error = MatError;
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+        match &self.0.data {
+            MatData::Numeric { real, .. } if real.len() == 1 => {
+                Scalar(real[0]).deserialize_any(visitor)
+            }
+            MatData::Char(text) => visitor.visit_str(text),
+            MatData::Struct { fields, elements } if elements.len() == 1 => Element {
+                fields,
+                values: &elements[0],
+            }
+            .deserialize_any(visitor),
+            _ => self.deserialize_seq(visitor),
+        }
+    }
+    scalar!(
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_f32,
+        deserialize_f64
+    );
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+        visitor.visit_unit()
+    }
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, MatError> {
+        visitor.visit_unit()
+    }
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, MatError> {
+        visitor.visit_newtype_struct(self)
+    }
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+        match &self.0.data {
+            MatData::Numeric { real, .. } => {
+                visitor.visit_seq(Seq(real.iter().map(|&v| Scalar(v))))
+            }
+            MatData::Struct { fields, elements } => visitor.visit_seq(Seq(elements
+                .iter()
+                .map(|values| Element { fields, values }))),
+            MatData::Cell(cells) => visitor.visit_seq(Seq(cells.iter().map(ArrayDeserializer))),
+            MatData::Char(_) => Err(Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, MatError> {
+        self.deserialize_seq(visitor)
+    }
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, MatError> {
+        self.deserialize_seq(visitor)
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, MatError> {
+        match &self.0.data {
+            MatData::Struct {
+                fields: names,
+                elements,
+            } if elements.len() == 1 => Element {
+                fields: names,
+                values: &elements[0],
+            }
+            .deserialize_struct(name, fields, visitor),
+            _ => Err(Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, MatError> {
+        match &self.0.data {
+            MatData::Char(text) => visitor.visit_enum(text.as_str().into_deserializer()),
+            _ => Err(Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, MatError> {
+        visitor.visit_unit()
+    }
+    forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf map identifier
+    }
+}
@@ -0,0 +1,352 @@
+//! MAT-file parser
+
+use super::{MatArray, MatClass, MatData, MatError, MatFile};
+use flate2::read::ZlibDecoder;
+use std::io::Read;
+
+pub(super) const MI_INT8: u32 = 1;
+pub(super) const MI_UINT8: u32 = 2;
+pub(super) const MI_INT16: u32 = 3;
+pub(super) const MI_UINT16: u32 = 4;
+pub(super) const MI_INT32: u32 = 5;
+pub(super) const MI_UINT32: u32 = 6;
+pub(super) const MI_SINGLE: u32 = 7;
+pub(super) const MI_DOUBLE: u32 = 9;
+pub(super) const MI_INT64: u32 = 12;
+pub(super) const MI_UINT64: u32 = 13;
+pub(super) const MI_MATRIX: u32 = 14;
+const MI_COMPRESSED: u32 = 15;
+const MI_UTF8: u32 = 16;
+const MI_UTF16: u32 = 17;
+const MI_UTF32: u32 = 18;
+
+pub(super) const MX_CELL: u32 = 1;
+pub(super) const MX_STRUCT: u32 = 2;
+pub(super) const MX_CHAR: u32 = 4;
+
+/// Complex array flag
+pub(super) const COMPLEX: u32 = 0x0800;
+/// Logical array flag
+pub(super) const LOGICAL: u32 = 0x0200;
+
+/// Byte order of the MAT-file
+#[derive(Debug, Clone, Copy)]
+enum Endian {
+    Little,
+    Big,
+}
+impl Endian {
+    fn u16(self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+        match self {
+            Endian::Little => u16::from_le_bytes(bytes),
+            Endian::Big => u16::from_be_bytes(bytes),
+        }
+    }
+    fn u32(self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match self {
+            Endian::Little => u32::from_le_bytes(bytes),
+            Endian::Big => u32::from_be_bytes(bytes),
+        }
+    }
+    fn u64(self, bytes: &[u8]) -> u64 {
+        let mut buffer = [0u8; 8];
+        buffer.copy_from_slice(&bytes[..8]);
+        match self {
+            Endian::Little => u64::from_le_bytes(buffer),
+            Endian::Big => u64::from_be_bytes(buffer),
+        }
+    }
+    /// Converts the data of an element into `f64` values
+    fn numbers(self, dtype: u32, data: &[u8]) -> Result<Vec<f64>, MatError> {
+        let size = match dtype {
+            MI_INT8 | MI_UINT8 => 1,
+            MI_INT16 | MI_UINT16 => 2,
+            MI_INT32 | MI_UINT32 | MI_SINGLE => 4,
+            MI_DOUBLE | MI_INT64 | MI_UINT64 => 8,
+            _ => {
+                return Err(MatError::Format(format!(
+                    "data type {} is not numeric",
+                    dtype
+                )))
+            }
+        };
+        if data.len() % size != 0 {
+            return Err(MatError::Format(format!(
+                "{} bytes of data type {}",
+                data.len(),
+                dtype
+            )));
+        }
+        Ok(data
+            .chunks_exact(size)
+            .map(|bytes| match dtype {
+                MI_INT8 => bytes[0] as i8 as f64,
+                MI_UINT8 => bytes[0] as f64,
+                MI_INT16 => self.u16(bytes) as i16 as f64,
+                MI_UINT16 => self.u16(bytes) as f64,
+                MI_INT32 => self.u32(bytes) as i32 as f64,
+                MI_UINT32 => self.u32(bytes) as f64,
+                MI_SINGLE => f32::from_bits(self.u32(bytes)) as f64,
+                MI_DOUBLE => f64::from_bits(self.u64(bytes)),
+                MI_INT64 => self.u64(bytes) as i64 as f64,
+                _ => self.u64(bytes) as f64,
+            })
+            .collect())
+    }
+}
+
+/// Sequence of data elements
+struct Elements<'a> {
+    bytes: &'a [u8],
+    endian: Endian,
+}
+impl<'a> Elements<'a> {
+    /// Returns the data type and the data of the next element
+    fn next(&mut self) -> Result<Option<(u32, &'a [u8])>, MatError> {
+        // trailing padding
+        if self.bytes.len() < 8 {
+            return Ok(None);
+        }
+        let tag = self.endian.u32(&self.bytes[..4]);
+        if tag >> 16 != 0 {
+            // small data element, packed into the tag
+            let n = (tag >> 16) as usize;
+            if n > 4 {
+                return Err(MatError::Format(format!(
+                    "small data element of {} bytes",
+                    n
+                )));
+            }
+            let data = &self.bytes[4..4 + n];
+            self.bytes = &self.bytes[8..];
+            return Ok(Some((tag & 0xffff, data)));
+        }
+        let n = self.endian.u32(&self.bytes[4..8]) as usize;
+        if self.bytes.len() < 8 + n {
+            return Err(MatError::Format(format!(
+                "data element of {} bytes, {} bytes left",
+                n,
+                self.bytes.len() - 8
+            )));
+        }
+        let data = &self.bytes[8..8 + n];
+        // the compressed elements are not padded
+        let end = if tag == MI_COMPRESSED {
+            8 + n
+        } else {
+            (8 + n).next_multiple_of(8).min(self.bytes.len())
+        };
+        self.bytes = &self.bytes[end..];
+        Ok(Some((tag, data)))
+    }
+    /// Returns the data of the next element, that must exist
+    fn expect(&mut self, what: &str) -> Result<(u32, &'a [u8]), MatError> {
+        self.next()?
+            .ok_or_else(|| MatError::Format(format!("missing {}", what)))
+    }
+}
+
+/// Parses a MAT-file
+pub(super) fn mat_file(bytes: &[u8]) -> Result<MatFile, MatError> {
+    if bytes.len() < 128 {
+        return Err(MatError::Format("the header is too short".into()));
+    }
+    let endian = match &bytes[126..128] {
+        b"IM" => Endian::Little,
+        b"MI" => Endian::Big,
+        _ => return Err(MatError::Format("invalid endian indicator".into())),
+    };
+    match endian.u16(&bytes[124..126]) {
+        0x0100 => (),
+        0x0200 => {
+            return Err(MatError::Unsupported(
+                "version 7.3, save the file with the -v7 option".into(),
+            ))
+        }
+        version => return Err(MatError::Unsupported(format!("version {:#06x}", version))),
+    }
+    let mut arrays = vec![];
+    let mut elements = Elements {
+        bytes: &bytes[128..],
+        endian,
+    };
+    while let Some((dtype, data)) = elements.next()? {
+        match dtype {
+            MI_MATRIX => arrays.push(array(data, endian)?),
+            MI_COMPRESSED => {
+                let mut inflated = vec![];
+                ZlibDecoder::new(data).read_to_end(&mut inflated)?;
+                let mut elements = Elements {
+                    bytes: &inflated,
+                    endian,
+                };
+                while let Some((dtype, data)) = elements.next()? {
+                    if dtype == MI_MATRIX {
+                        arrays.push(array(data, endian)?);
+                    }
+                }
+            }
+            _ => {
+                return Err(MatError::Format(format!(
+                    "unexpected data type {} at the top level",
+                    dtype
+                )))
+            }
+        }
+    }
+    Ok(MatFile { arrays })
+}
+
+/// Parses the data of a `miMATRIX` element
+fn array(data: &[u8], endian: Endian) -> Result<MatArray, MatError> {
+    if data.is_empty() {
+        // empty arrays may be written without sub-elements
+        return Ok(MatArray::double("", vec![0, 0], vec![]));
+    }
+    let mut elements = Elements {
+        bytes: data,
+        endian,
+    };
+    let (_, flags) = elements.expect("array flags")?;
+    if flags.len() < 4 {
+        return Err(MatError::Format("invalid array flags".into()));
+    }
+    let flags = endian.u32(flags);
+    let (dtype, dims) = elements.expect("array dimensions")?;
+    let dims: Vec<usize> = endian
+        .numbers(dtype, dims)?
+        .into_iter()
+        .map(|dim| dim as usize)
+        .collect();
+    let (_, name) = elements.expect("array name")?;
+    let name = String::from_utf8_lossy(name).into_owned();
+    let n = dims
+        .iter()
+        .try_fold(1usize, |n, &dim| n.checked_mul(dim))
+        .ok_or_else(|| {
+            MatError::Format(format!(
+                "array {} dimensions {:?} overflow the number of elements",
+                name, dims
+            ))
+        })?;
+    let class = flags & 0xff;
+    let data = match class {
+        MX_CELL => MatData::Cell(
+            (0..n)
+                .map(|_| matrix(&mut elements, endian))
+                .collect::<Result<_, _>>()?,
+        ),
+        MX_STRUCT => {
+            let (dtype, length) = elements.expect("field name length")?;
+            let length = endian
+                .numbers(dtype, length)?
+                .first()
+                .copied()
+                .unwrap_or(0.) as usize;
+            let (_, names) = elements.expect("field names")?;
+            let fields: Vec<String> = if length == 0 {
+                vec![]
+            } else {
+                names
+                    .chunks(length)
+                    .map(|name| {
+                        let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+                        String::from_utf8_lossy(&name[..end]).into_owned()
+                    })
+                    .collect()
+            };
+            let elements = (0..n)
+                .map(|_| {
+                    fields
+                        .iter()
+                        .map(|_| matrix(&mut elements, endian))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .collect::<Result<_, _>>()?;
+            MatData::Struct { fields, elements }
+        }
+        MX_CHAR => {
+            let (dtype, data) = elements.next()?.unwrap_or((MI_UTF8, &[]));
+            let chars: Vec<char> = match dtype {
+                MI_UTF8 => String::from_utf8_lossy(data).chars().collect(),
+                MI_UTF16 | MI_UINT16 => {
+                    char::decode_utf16(data.chunks_exact(2).map(|bytes| endian.u16(bytes)))
+                        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                        .collect()
+                }
+                MI_UTF32 => data
+                    .chunks_exact(4)
+                    .map(|bytes| {
+                        char::from_u32(endian.u32(bytes)).unwrap_or(char::REPLACEMENT_CHARACTER)
+                    })
+                    .collect(),
+                _ => endian
+                    .numbers(dtype, data)?
+                    .into_iter()
+                    .map(|c| char::from_u32(c as u32).unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect(),
+            };
+            // the char matrices are stored column-major
+            let rows = dims.first().copied().unwrap_or(1).max(1);
+            let columns = chars.len() / rows;
+            let text = (0..rows)
+                .map(|i| {
+                    (0..columns)
+                        .map(|j| chars[i + j * rows])
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            MatData::Char(text)
+        }
+        6..=15 => {
+            let class = match class {
+                6 => MatClass::Double,
+                7 => MatClass::Single,
+                8 => MatClass::Int8,
+                9 if flags & LOGICAL != 0 => MatClass::Logical,
+                9 => MatClass::Uint8,
+                10 => MatClass::Int16,
+                11 => MatClass::Uint16,
+                12 => MatClass::Int32,
+                13 => MatClass::Uint32,
+                14 => MatClass::Int64,
+                _ => MatClass::Uint64,
+            };
+            let (dtype, real) = elements.expect("real part")?;
+            let real = endian.numbers(dtype, real)?;
+            let imag = if flags & COMPLEX != 0 {
+                let (dtype, imag) = elements.expect("imaginary part")?;
+                Some(endian.numbers(dtype, imag)?)
+            } else {
+                None
+            };
+            if real.len() != n {
+                return Err(MatError::Format(format!(
+                    "array {} has {} values, expected {}",
+                    name,
+                    real.len(),
+                    n
+                )));
+            }
+            MatData::Numeric { class, real, imag }
+        }
+        3 => return Err(MatError::Unsupported(format!("object {}", name))),
+        5 => return Err(MatError::Unsupported(format!("sparse array {}", name))),
+        _ => return Err(MatError::Unsupported(format!("array class {}", class))),
+    };
+    Ok(MatArray { name, dims, data })
+}
+
+/// Parses the next element as a `miMATRIX` element
+fn matrix(elements: &mut Elements, endian: Endian) -> Result<MatArray, MatError> {
+    match elements.expect("array")? {
+        (MI_MATRIX, data) => array(data, endian),
+        (dtype, _) => Err(MatError::Format(format!(
+            "expected an array, found data type {}",
+            dtype
+        ))),
+    }
+}
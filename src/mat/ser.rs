@@ -0,0 +1,347 @@
+//! Conversion of Rust types into MATLAB arrays
+
+use super::{MatArray, MatClass, MatData, MatError};
+use serde::{
+    ser::{self, Error, Impossible},
+    Serialize,
+};
+
+/// Returns a numeric scalar
+fn scalar(class: MatClass, value: f64) -> MatArray {
+    MatArray {
+        name: String::new(),
+        dims: vec![1, 1],
+        data: MatData::Numeric {
+            class,
+            real: vec![value],
+            imag: None,
+        },
+    }
+}
+
+/// Returns a char array
+fn char_array(text: &str) -> MatArray {
+    MatArray {
+        name: String::new(),
+        dims: vec![1, text.encode_utf16().count()],
+        data: MatData::Char(text.to_string()),
+    }
+}
+
+/// Returns an empty array
+fn empty() -> MatArray {
+    MatArray::double("", vec![0, 0], vec![])
+}
+
+/// Serializer of a MATLAB array
+///
+/// A number is a numeric scalar of the class of the Rust type, a string is a char array,
+/// a structure or a map is a scalar structure, and a sequence is a column vector
+/// if its elements are numbers of the same class, a structure array if its elements are
+/// structures with the same fields, or else a cell array
+pub(super) struct ArraySerializer;
+
+macro_rules! number {
+    ($($serialize:ident $ty:ty => $class:ident),*) => {
+        $(
+            fn $serialize(self, v: $ty) -> Result<MatArray, MatError> {
+                Ok(scalar(MatClass::$class, v as f64))
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for ArraySerializer {
+    type Ok = MatArray;
+    type Error = MatError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = Impossible<MatArray, MatError>;
+    type SerializeMap = StructSerializer;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = Impossible<MatArray, MatError>;
+
+    number!(
+        serialize_i8 i8 => Int8,
+        serialize_u8 u8 => Uint8,
+        serialize_i16 i16 => Int16,
+        serialize_u16 u16 => Uint16,
+        serialize_i32 i32 => Int32,
+        serialize_u32 u32 => Uint32,
+        serialize_i64 i64 => Int64,
+        serialize_u64 u64 => Uint64,
+        serialize_f32 f32 => Single,
+        serialize_f64 f64 => Double
+    );
+    fn serialize_bool(self, v: bool) -> Result<MatArray, MatError> {
+        Ok(scalar(MatClass::Logical, v as u8 as f64))
+    }
+    fn serialize_char(self, v: char) -> Result<MatArray, MatError> {
+        Ok(char_array(v.encode_utf8(&mut [0; 4])))
+    }
+    fn serialize_str(self, v: &str) -> Result<MatArray, MatError> {
+        Ok(char_array(v))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<MatArray, MatError> {
+        Ok(MatArray {
+            name: String::new(),
+            dims: vec![v.len(), 1],
+            data: MatData::Numeric {
+                class: MatClass::Uint8,
+                real: v.iter().map(|&v| v as f64).collect(),
+                imag: None,
+            },
+        })
+    }
+    fn serialize_none(self) -> Result<MatArray, MatError> {
+        Ok(empty())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<MatArray, MatError> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<MatArray, MatError> {
+        Ok(empty())
+    }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<MatArray, MatError> {
+        Ok(empty())
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<MatArray, MatError> {
+        Ok(char_array(variant))
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<MatArray, MatError> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<MatArray, MatError> {
+        Ok(MatArray {
+            name: String::new(),
+            dims: vec![1, 1],
+            data: MatData::Struct {
+                fields: vec![variant.to_string()],
+                elements: vec![vec![value.serialize(self)?]],
+            },
+        })
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, MatError> {
+        Ok(SeqSerializer(Vec::with_capacity(len.unwrap_or_default())))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, MatError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, MatError> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Impossible<MatArray, MatError>, MatError> {
+        Err(MatError::custom(format!(
+            "tuple variant {}::{} cannot be saved into a MAT-file",
+            name, variant
+        )))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<StructSerializer, MatError> {
+        Ok(StructSerializer::default())
+    }
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<StructSerializer, MatError> {
+        Ok(StructSerializer::default())
+    }
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Impossible<MatArray, MatError>, MatError> {
+        Err(MatError::custom(format!(
+            "struct variant {}::{} cannot be saved into a MAT-file",
+            name, variant
+        )))
+    }
+}
+
+/// Serializer of the elements of a sequence
+pub(super) struct SeqSerializer(Vec<MatArray>);
+impl SeqSerializer {
+    fn push<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MatError> {
+        self.0.push(value.serialize(ArraySerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<MatArray, MatError> {
+        let n = self.0.len();
+        if n == 0 {
+            return Ok(empty());
+        }
+        let dims = vec![n, 1];
+        // numbers of the same class
+        let class = match &self.0[0].data {
+            MatData::Numeric {
+                class, imag: None, ..
+            } => Some(*class),
+            _ => None,
+        };
+        if let Some(class) = class {
+            let real: Option<Vec<f64>> = self
+                .0
+                .iter()
+                .map(|array| match &array.data {
+                    MatData::Numeric {
+                        class: c,
+                        real,
+                        imag: None,
+                    } if *c == class && real.len() == 1 => Some(real[0]),
+                    _ => None,
+                })
+                .collect();
+            if let Some(real) = real {
+                return Ok(MatArray {
+                    name: String::new(),
+                    dims,
+                    data: MatData::Numeric {
+                        class,
+                        real,
+                        imag: None,
+                    },
+                });
+            }
+        }
+        // structures with the same fields
+        if let MatData::Struct { fields, .. } = &self.0[0].data {
+            let fields = fields.clone();
+            let is_struct_array = self.0.iter().all(|array| {
+                matches!(&array.data, MatData::Struct { fields: f, elements } if *f == fields && elements.len() == 1)
+            });
+            if is_struct_array {
+                let elements = self
+                    .0
+                    .into_iter()
+                    .filter_map(|array| match array.data {
+                        MatData::Struct { elements, .. } => elements.into_iter().next(),
+                        _ => None,
+                    })
+                    .collect();
+                return Ok(MatArray {
+                    name: String::new(),
+                    dims,
+                    data: MatData::Struct { fields, elements },
+                });
+            }
+        }
+        Ok(MatArray {
+            name: String::new(),
+            dims,
+            data: MatData::Cell(self.0),
+        })
+    }
+}
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = MatArray;
+    type Error = MatError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MatError> {
+        self.push(value)
+    }
+    fn end(self) -> Result<MatArray, MatError> {
+        SeqSerializer::end(self)
+    }
+}
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = MatArray;
+    type Error = MatError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MatError> {
+        self.push(value)
+    }
+    fn end(self) -> Result<MatArray, MatError> {
+        SeqSerializer::end(self)
+    }
+}
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = MatArray;
+    type Error = MatError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MatError> {
+        self.push(value)
+    }
+    fn end(self) -> Result<MatArray, MatError> {
+        SeqSerializer::end(self)
+    }
+}
+
+/// Serializer of the fields of a structure
+#[derive(Default)]
+pub(super) struct StructSerializer {
+    fields: Vec<String>,
+    values: Vec<MatArray>,
+}
+impl StructSerializer {
+    fn end(self) -> MatArray {
+        MatArray {
+            name: String::new(),
+            dims: vec![1, 1],
+            data: MatData::Struct {
+                fields: self.fields,
+                elements: vec![self.values],
+            },
+        }
+    }
+}
+impl ser::SerializeStruct for StructSerializer {
+    type Ok = MatArray;
+    type Error = MatError;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), MatError> {
+        self.fields.push(key.to_string());
+        self.values.push(value.serialize(ArraySerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<MatArray, MatError> {
+        Ok(StructSerializer::end(self))
+    }
+}
+impl ser::SerializeMap for StructSerializer {
+    type Ok = MatArray;
+    type Error = MatError;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), MatError> {
+        match key.serialize(ArraySerializer)?.data {
+            MatData::Char(key) => {
+                self.fields.push(key);
+                Ok(())
+            }
+            _ => Err(MatError::custom("the keys of a map must be strings")),
+        }
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), MatError> {
+        self.values.push(value.serialize(ArraySerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<MatArray, MatError> {
+        Ok(StructSerializer::end(self))
+    }
+}
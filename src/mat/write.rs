@@ -0,0 +1,270 @@
+//! MAT-file writer
+
+use super::{
+    read::{
+        COMPLEX, LOGICAL, MI_DOUBLE, MI_INT16, MI_INT32, MI_INT64, MI_INT8, MI_MATRIX, MI_SINGLE,
+        MI_UINT16, MI_UINT32, MI_UINT64, MI_UINT8, MX_CELL, MX_CHAR, MX_STRUCT,
+    },
+    MatArray, MatClass, MatData, MatError, MatFile,
+};
+
+/// Writes a MAT-file, little-endian and uncompressed
+pub(super) fn mat_file(mat: &MatFile) -> Result<Vec<u8>, MatError> {
+    let mut bytes = format!(
+        "MATLAB 5.0 MAT-file, Platform: {}, Created by: simulink-rs {}",
+        std::env::consts::OS,
+        env!("CARGO_PKG_VERSION")
+    )
+    .into_bytes();
+    bytes.resize(116, b' ');
+    bytes.extend([0u8; 8]);
+    bytes.extend(0x0100u16.to_le_bytes());
+    bytes.extend(b"IM");
+    for array in &mat.arrays {
+        matrix(&mut bytes, array, &array.name)?;
+    }
+    Ok(bytes)
+}
+
+/// Writes a data element, padded to 8 bytes
+fn element(bytes: &mut Vec<u8>, dtype: u32, data: &[u8]) {
+    if !data.is_empty() && data.len() <= 4 {
+        // small data element
+        bytes.extend(((data.len() as u32) << 16 | dtype).to_le_bytes());
+        bytes.extend(data);
+        bytes.resize(bytes.len() + 4 - data.len(), 0);
+    } else {
+        bytes.extend(dtype.to_le_bytes());
+        bytes.extend((data.len() as u32).to_le_bytes());
+        bytes.extend(data);
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+    }
+}
+
+/// Writes numeric values as the data type of the class
+fn numbers(bytes: &mut Vec<u8>, class: MatClass, values: &[f64]) {
+    let (dtype, data): (u32, Vec<u8>) = match class {
+        MatClass::Double => (
+            MI_DOUBLE,
+            values.iter().flat_map(|v| v.to_le_bytes()).collect(),
+        ),
+        MatClass::Single => (
+            MI_SINGLE,
+            values
+                .iter()
+                .flat_map(|&v| (v as f32).to_le_bytes())
+                .collect(),
+        ),
+        MatClass::Int8 => (MI_INT8, values.iter().map(|&v| v as i8 as u8).collect()),
+        MatClass::Uint8 | MatClass::Logical => {
+            (MI_UINT8, values.iter().map(|&v| v as u8).collect())
+        }
+        MatClass::Int16 => (
+            MI_INT16,
+            values
+                .iter()
+                .flat_map(|&v| (v as i16).to_le_bytes())
+                .collect(),
+        ),
+        MatClass::Uint16 => (
+            MI_UINT16,
+            values
+                .iter()
+                .flat_map(|&v| (v as u16).to_le_bytes())
+                .collect(),
+        ),
+        MatClass::Int32 => (
+            MI_INT32,
+            values
+                .iter()
+                .flat_map(|&v| (v as i32).to_le_bytes())
+                .collect(),
+        ),
+        MatClass::Uint32 => (
+            MI_UINT32,
+            values
+                .iter()
+                .flat_map(|&v| (v as u32).to_le_bytes())
+                .collect(),
+        ),
+        MatClass::Int64 => (
+            MI_INT64,
+            values
+                .iter()
+                .flat_map(|&v| (v as i64).to_le_bytes())
+                .collect(),
+        ),
+        MatClass::Uint64 => (
+            MI_UINT64,
+            values
+                .iter()
+                .flat_map(|&v| (v as u64).to_le_bytes())
+                .collect(),
+        ),
+    };
+    element(bytes, dtype, &data);
+}
+
+/// Returns the MATLAB class code of a numeric array
+fn class_code(class: MatClass) -> u32 {
+    match class {
+        MatClass::Double => 6,
+        MatClass::Single => 7,
+        MatClass::Int8 => 8,
+        MatClass::Uint8 | MatClass::Logical => 9,
+        MatClass::Int16 => 10,
+        MatClass::Uint16 => 11,
+        MatClass::Int32 => 12,
+        MatClass::Uint32 => 13,
+        MatClass::Int64 => 14,
+        MatClass::Uint64 => 15,
+    }
+}
+
+/// Returns the dimensions and the UTF-16 characters, in column-major order, of a char matrix
+///
+/// Each line of the text is a row of the matrix, padded with spaces
+fn char_matrix(text: &str) -> (Vec<usize>, Vec<u16>) {
+    let rows: Vec<Vec<u16>> = text
+        .split('\n')
+        .map(|row| row.encode_utf16().collect())
+        .collect();
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return (vec![0, 0], vec![]);
+    }
+    let chars = (0..columns)
+        .flat_map(|j| {
+            rows.iter()
+                .map(move |row| row.get(j).copied().unwrap_or(b' ' as u16))
+        })
+        .collect();
+    (vec![rows.len(), columns], chars)
+}
+
+/// Writes an array as a `miMATRIX` element
+fn matrix(bytes: &mut Vec<u8>, array: &MatArray, name: &str) -> Result<(), MatError> {
+    let mut data = vec![];
+    let mut dims = array.dims.clone();
+    let flags = match &array.data {
+        MatData::Numeric { class, real, imag } => {
+            if real.len() != array.len()
+                || imag.as_ref().is_some_and(|imag| imag.len() != real.len())
+            {
+                return Err(MatError::Format(format!(
+                    "array {} has {} values, expected {}",
+                    name,
+                    real.len(),
+                    array.len()
+                )));
+            }
+            let mut flags = class_code(*class);
+            if imag.is_some() {
+                flags |= COMPLEX;
+            }
+            if *class == MatClass::Logical {
+                flags |= LOGICAL;
+            }
+            flags
+        }
+        MatData::Char(text) => {
+            dims = char_matrix(text).0;
+            MX_CHAR
+        }
+        MatData::Struct { elements, .. } => {
+            if elements.len() != array.len() {
+                return Err(MatError::Format(format!(
+                    "structure {} has {} elements, expected {}",
+                    name,
+                    elements.len(),
+                    array.len()
+                )));
+            }
+            MX_STRUCT
+        }
+        MatData::Cell(cells) => {
+            if cells.len() != array.len() {
+                return Err(MatError::Format(format!(
+                    "cell array {} has {} cells, expected {}",
+                    name,
+                    cells.len(),
+                    array.len()
+                )));
+            }
+            MX_CELL
+        }
+    };
+    element(
+        &mut data,
+        MI_UINT32,
+        &[flags.to_le_bytes(), 0u32.to_le_bytes()].concat(),
+    );
+    let dims: Vec<u8> = dims
+        .iter()
+        .flat_map(|&dim| (dim as i32).to_le_bytes())
+        .collect();
+    element(&mut data, MI_INT32, &dims);
+    element(&mut data, MI_INT8, name.as_bytes());
+    match &array.data {
+        MatData::Numeric { class, real, imag } => {
+            numbers(&mut data, *class, real);
+            if let Some(imag) = imag {
+                numbers(&mut data, *class, imag);
+            }
+        }
+        MatData::Char(text) => {
+            let chars: Vec<u8> = char_matrix(text)
+                .1
+                .into_iter()
+                .flat_map(|c| c.to_le_bytes())
+                .collect();
+            element(&mut data, MI_UINT16, &chars);
+        }
+        MatData::Struct { fields, elements } => {
+            let length = fields
+                .iter()
+                .map(|field| field.len() + 1)
+                .max()
+                .unwrap_or(0)
+                .max(32);
+            if length > 64 {
+                return Err(MatError::Unsupported(format!(
+                    "field names longer than 63 characters in structure {}",
+                    name
+                )));
+            }
+            element(&mut data, MI_INT32, &(length as i32).to_le_bytes());
+            let names: Vec<u8> = fields
+                .iter()
+                .flat_map(|field| {
+                    let mut name = field.as_bytes().to_vec();
+                    name.resize(length, 0);
+                    name
+                })
+                .collect();
+            element(&mut data, MI_INT8, &names);
+            for values in elements {
+                if values.len() != fields.len() {
+                    return Err(MatError::Format(format!(
+                        "structure {} has {} fields, found {} values",
+                        name,
+                        fields.len(),
+                        values.len()
+                    )));
+                }
+                for value in values {
+                    matrix(&mut data, value, "")?;
+                }
+            }
+        }
+        MatData::Cell(cells) => {
+            for cell in cells {
+                matrix(&mut data, cell, "")?;
+            }
+        }
+    }
+    bytes.extend(MI_MATRIX.to_le_bytes());
+    bytes.extend((data.len() as u32).to_le_bytes());
+    bytes.extend(data);
+    Ok(())
+}
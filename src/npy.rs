@@ -0,0 +1,433 @@
+//! NumPy recorder of the controller signals
+//!
+//! A [Recorder] streams some input and output signals of a controller, at each step,
+//! either into one `.npy` file per signal, or into a single `.npz` archive.
+//! The signals are selected by their Simulink or snake case names, and the shape and the type
+//! of the NumPy arrays are derived from the [SignalInfo] table of the controller,
+//! so no per-model code is needed.
+//! Each array has one row per step, i.e. the shape of a signal of size `n` recorded over
+//! `k` steps is `(k, n)`, or `(k,)` for a scalar signal.
+//!
+//! # Example
+//! ```ignore
+//! let mut controller = MySimulinkController::new();
+//! let mut recorder = Recorder::npz(&controller, "run.npz", &["Res_Act_F"])?;
+//! for _ in 0..1000 {
+//!     controller.step();
+//!     recorder.record(&controller)?;
+//! }
+//! recorder.finish()?;
+//! ```
+//! and in Python
+//! ```python
+//! import numpy as np
+//! res_act_f = np.load("run.npz")["Res_Act_F"]
+//! ```
+
+use crate::{Direction, SignalError, SignalInfo, SimulinkController};
+use simulink_model::snake_case;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    mem,
+    path::{Path, PathBuf},
+    slice,
+};
+
+/// Returns the NumPy type and the size in bytes of a C data type
+fn dtype(dtype: &str) -> Option<(&'static str, usize)> {
+    let (descr, size) = match dtype {
+        "real_T" | "real64_T" | "double" => ("f8", 8),
+        "real32_T" | "float" => ("f4", 4),
+        "int8_T" | "char_T" => ("i1", 1),
+        "uint8_T" => ("u1", 1),
+        "boolean_T" => ("b1", 1),
+        "int16_T" => ("i2", 2),
+        "uint16_T" => ("u2", 2),
+        "int32_T" | "int" => ("i4", 4),
+        "uint32_T" => ("u4", 4),
+        "int64_T" => ("i8", 8),
+        "uint64_T" => ("u8", 8),
+        _ => return None,
+    };
+    Some((descr, size))
+}
+
+/// Returns the header of a `.npy` file
+///
+/// The header is padded to the same length whatever the number of steps, so that it can be
+/// rewritten once the number of steps is known
+fn header(descr: &str, width: usize, steps: usize) -> Vec<u8> {
+    let shape = |steps: String| match width {
+        1 => format!("({},)", steps),
+        _ => format!("({}, {})", steps, width),
+    };
+    let dict = |shape: String| {
+        format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+            descr, shape
+        )
+    };
+    // magic string, version, header length, dictionary and new line
+    let len = (10 + dict(shape(u64::MAX.to_string())).len() + 1).next_multiple_of(64);
+    let mut header = b"\x93NUMPY\x01\x00".to_vec();
+    header.extend(((len - 10) as u16).to_le_bytes());
+    header.extend(dict(shape(steps.to_string())).into_bytes());
+    header.resize(len - 1, b' ');
+    header.push(b'\n');
+    header
+}
+
+/// Recorded signal
+#[derive(Debug)]
+struct Channel {
+    info: SignalInfo,
+    descr: String,
+    bytes: usize,
+}
+impl Channel {
+    fn header(&self, steps: usize) -> Vec<u8> {
+        header(&self.descr, self.info.size, steps)
+    }
+}
+
+/// `.npz` archive of the `.npy` files of the signals
+#[derive(Debug)]
+struct Archive {
+    path: PathBuf,
+    /// Directory of the `.npy` files, removed once they are archived
+    spool: PathBuf,
+}
+
+/// NumPy recorder of the controller signals
+#[derive(Debug)]
+pub struct Recorder<C> {
+    channels: Vec<Channel>,
+    files: Vec<BufWriter<File>>,
+    archive: Option<Archive>,
+    steps: usize,
+    finished: bool,
+    controller: PhantomData<C>,
+}
+
+impl<C: SimulinkController> Recorder<C> {
+    /// Selects the signals of the controller, or all its numeric signals if `names` is empty
+    fn channels(controller: &C, names: &[&str]) -> io::Result<Vec<Channel>> {
+        let signals = controller.signals();
+        let selected: Vec<&SignalInfo> = if names.is_empty() {
+            signals
+                .iter()
+                .filter(|signal| dtype(signal.dtype).is_some())
+                .collect()
+        } else {
+            names
+                .iter()
+                .map(|&name| {
+                    signals
+                        .iter()
+                        .find(|signal| signal.name == name)
+                        .or_else(|| {
+                            signals
+                                .iter()
+                                .find(|signal| snake_case(signal.name) == name)
+                        })
+                        .ok_or_else(|| SignalError::NotFound(name.to_string()))
+                })
+                .collect::<Result<_, _>>()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+        };
+        selected
+            .into_iter()
+            .map(|&info| {
+                let (descr, size) = dtype(info.dtype).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("signal {} of type {} is not numeric", info.name, info.dtype),
+                    )
+                })?;
+                // the signal must lie within its structure, as it is read from raw memory
+                let structure = match info.direction {
+                    Direction::Input => mem::size_of::<C::Inputs>(),
+                    Direction::Output => mem::size_of::<C::Outputs>(),
+                };
+                let end = size
+                    .checked_mul(info.size)
+                    .and_then(|bytes| info.offset.checked_add(bytes));
+                if !matches!(end, Some(end) if end <= structure) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "signal {} of {} elements at offset {} overflows its structure of {} bytes",
+                            info.name,
+                            info.size,
+                            info.offset,
+                            structure
+                        ),
+                    ));
+                }
+                let endian = if size == 1 {
+                    '|'
+                } else if cfg!(target_endian = "big") {
+                    '>'
+                } else {
+                    '<'
+                };
+                Ok(Channel {
+                    info,
+                    descr: format!("{}{}", endian, descr),
+                    bytes: size * info.size,
+                })
+            })
+            .collect()
+    }
+    /// Creates a recorder that streams each signal into the `.npy` file `<dir>/<signal>.npy`
+    ///
+    /// The signals are given by their Simulink or snake case names, all the numeric signals
+    /// are recorded if `names` is empty
+    pub fn npy<P: AsRef<Path>>(controller: &C, dir: P, names: &[&str]) -> io::Result<Self> {
+        let channels = Self::channels(controller, names)?;
+        let files = create(&channels, dir.as_ref())?;
+        Ok(Self {
+            channels,
+            files,
+            archive: None,
+            steps: 0,
+            finished: false,
+            controller: PhantomData,
+        })
+    }
+    /// Creates a recorder that archives the signals into the `.npz` file `path`
+    ///
+    /// The signals are given by their Simulink or snake case names, all the numeric signals
+    /// are recorded if `names` is empty.
+    /// The signals are streamed into the `.npy` files of the directory `<path>.parts`,
+    /// that are moved into the archive by [Recorder::finish]
+    pub fn npz<P: AsRef<Path>>(controller: &C, path: P, names: &[&str]) -> io::Result<Self> {
+        let channels = Self::channels(controller, names)?;
+        let path = path.as_ref().to_path_buf();
+        // fails early if the archive cannot be created
+        File::create(&path)?;
+        let mut spool = path.clone().into_os_string();
+        spool.push(".parts");
+        let spool = PathBuf::from(spool);
+        fs::create_dir_all(&spool)?;
+        let files = create(&channels, &spool)?;
+        Ok(Self {
+            channels,
+            files,
+            archive: Some(Archive { path, spool }),
+            steps: 0,
+            finished: false,
+            controller: PhantomData,
+        })
+    }
+    /// Returns the Simulink names of the recorded signals
+    pub fn signals(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.channels.iter().map(|channel| channel.info.name)
+    }
+    /// Returns the number of recorded steps
+    pub fn len(&self) -> usize {
+        self.steps
+    }
+    /// Checks if no step has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.steps == 0
+    }
+    /// Records the current values of the signals of the controller
+    pub fn record(&mut self, controller: &C) -> io::Result<()> {
+        self.record_io(controller.inputs(), controller.outputs())
+    }
+    /// Records the values of the signals of the inputs and outputs structures
+    pub fn record_io(&mut self, inputs: &C::Inputs, outputs: &C::Outputs) -> io::Result<()> {
+        for (channel, file) in self.channels.iter().zip(&mut self.files) {
+            let structure = match channel.info.direction {
+                Direction::Input => inputs as *const C::Inputs as *const u8,
+                Direction::Output => outputs as *const C::Outputs as *const u8,
+            };
+            // the signal bytes are read as they are laid out in the structure,
+            // the channels are checked to lie within the structures by `Recorder::channels`
+            let data =
+                unsafe { slice::from_raw_parts(structure.add(channel.info.offset), channel.bytes) };
+            file.write_all(data)?;
+        }
+        self.steps += 1;
+        Ok(())
+    }
+}
+impl<C> Recorder<C> {
+    /// Writes the number of steps into the `.npy` files, or writes the `.npz` archive
+    pub fn finish(mut self) -> io::Result<()> {
+        self.close()
+    }
+    fn close(&mut self) -> io::Result<()> {
+        self.finished = true;
+        for (channel, file) in self.channels.iter().zip(&mut self.files) {
+            file.flush()?;
+            let file = file.get_mut();
+            file.seek(SeekFrom::Start(0))?;
+            file.write_all(&channel.header(self.steps))?;
+            file.seek(SeekFrom::End(0))?;
+        }
+        if let Some(Archive { path, spool }) = &self.archive {
+            let mut archive = Zip::new(BufWriter::new(File::create(path)?));
+            for (channel, file) in self.channels.iter().zip(&mut self.files) {
+                archive.add(&format!("{}.npy", channel.info.name), file.get_mut())?;
+            }
+            archive.finish()?.flush()?;
+            self.files.clear();
+            fs::remove_dir_all(spool)?;
+        }
+        Ok(())
+    }
+}
+impl<C> Drop for Recorder<C> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.close();
+        }
+    }
+}
+
+/// Creates the `.npy` files of the signals in `dir`, with the header of an empty array
+fn create(channels: &[Channel], dir: &Path) -> io::Result<Vec<BufWriter<File>>> {
+    channels
+        .iter()
+        .map(|channel| {
+            let path = dir.join(format!("{}.npy", channel.info.name));
+            // the files are read back to be archived
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?;
+            let mut file = BufWriter::new(file);
+            file.write_all(&channel.header(0))?;
+            Ok(file)
+        })
+        .collect()
+}
+
+/// Lookup table of the CRC-32 checksum
+const CRC32: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 == 1 {
+                0xedb88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 checksum of the zip entries
+fn crc32<R: Read>(mut reader: R) -> io::Result<u32> {
+    let mut crc = !0u32;
+    let mut buffer = [0u8; 8192];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(!crc),
+            Ok(n) => {
+                crc = buffer[..n].iter().fold(crc, |crc, &byte| {
+                    CRC32[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+                })
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Error of an archive that does not fit the 32 bits sizes and offsets of a zip file
+fn too_large() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "npz archives are limited to 4GB and 65535 signals",
+    )
+}
+
+/// Zip archive of uncompressed entries
+struct Zip<W> {
+    writer: W,
+    offset: u32,
+    entries: Vec<(String, u32, u32, u32)>,
+}
+impl<W: Write> Zip<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            offset: 0,
+            entries: vec![],
+        }
+    }
+    /// Writes an entry, copying the content of `file`
+    fn add(&mut self, name: &str, file: &mut File) -> io::Result<()> {
+        let size = u32::try_from(file.seek(SeekFrom::End(0))?).map_err(|_| too_large())?;
+        file.seek(SeekFrom::Start(0))?;
+        let crc = crc32(&mut *file)?;
+        file.seek(SeekFrom::Start(0))?;
+        let mut header = vec![];
+        header.extend(0x04034b50u32.to_le_bytes());
+        // version, flags, method, time and date (1980-01-01)
+        header.extend([20u16, 0, 0, 0, 0x21].iter().flat_map(|v| v.to_le_bytes()));
+        header.extend(crc.to_le_bytes());
+        header.extend(size.to_le_bytes());
+        header.extend(size.to_le_bytes());
+        header.extend((name.len() as u16).to_le_bytes());
+        header.extend(0u16.to_le_bytes());
+        header.extend(name.as_bytes());
+        let offset = self.offset;
+        self.offset = u32::try_from(header.len())
+            .ok()
+            .and_then(|len| offset.checked_add(len)?.checked_add(size))
+            .ok_or_else(too_large)?;
+        self.writer.write_all(&header)?;
+        io::copy(&mut file.take(size as u64), &mut self.writer)?;
+        self.entries.push((name.to_string(), crc, size, offset));
+        Ok(())
+    }
+    /// Writes the central directory and returns the writer
+    fn finish(mut self) -> io::Result<W> {
+        let mut directory = vec![];
+        for (name, crc, size, offset) in &self.entries {
+            directory.extend(0x02014b50u32.to_le_bytes());
+            // versions, flags, method, time and date
+            directory.extend(
+                [20u16, 20, 0, 0, 0, 0x21]
+                    .iter()
+                    .flat_map(|v| v.to_le_bytes()),
+            );
+            directory.extend(crc.to_le_bytes());
+            directory.extend(size.to_le_bytes());
+            directory.extend(size.to_le_bytes());
+            directory.extend((name.len() as u16).to_le_bytes());
+            // extra field and comment lengths, disk number, attributes
+            directory.extend([0u16; 4].iter().flat_map(|v| v.to_le_bytes()));
+            directory.extend(0u32.to_le_bytes());
+            directory.extend(offset.to_le_bytes());
+            directory.extend(name.as_bytes());
+        }
+        let n = u16::try_from(self.entries.len()).map_err(|_| too_large())?;
+        let len = u32::try_from(directory.len()).map_err(|_| too_large())?;
+        self.offset.checked_add(len).ok_or_else(too_large)?;
+        let mut end = vec![];
+        end.extend(0x06054b50u32.to_le_bytes());
+        end.extend([0u16, 0, n, n].iter().flat_map(|v| v.to_le_bytes()));
+        end.extend(len.to_le_bytes());
+        end.extend(self.offset.to_le_bytes());
+        end.extend(0u16.to_le_bytes());
+        self.writer.write_all(&directory)?;
+        self.writer.write_all(&end)?;
+        Ok(self.writer)
+    }
+}
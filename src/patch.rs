@@ -0,0 +1,41 @@
+use serde::{Deserialize, Deserializer};
+
+/// Partial update of a Simulink structure
+///
+/// A patch sets only the fields that it contains, the other fields keep their current values,
+/// e.g. to update a few inputs of a controller from JSON:
+/// ```ignore
+/// use simulink_rs::Patch;
+///
+/// controller
+///     .inputs
+///     .apply_patch(&mut serde_json::Deserializer::from_str(r#"{"SA_offsetF_cmd": [...]}"#))?;
+/// ```
+/// The arrays of a patch are still complete, with as many elements as the fields.
+/// The structure is left unchanged if the patch fails.
+pub trait Patch {
+    /// Sets the fields of the structure that are given by the deserializer
+    fn apply_patch<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// Simulink structure deserialized from a [Patch] of the default structure
+///
+/// The fields that are not given keep their default values instead of failing
+/// the deserialization:
+/// ```ignore
+/// use simulink_rs::Partial;
+///
+/// let Partial(inputs) = serde_json::from_str::<Partial<ExtU_M1SA_Control_OA_T>>(json)?;
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Partial<T>(pub T);
+
+impl<'de, T: Patch + Default> Deserialize<'de> for Partial<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = T::default();
+        value.apply_patch(deserializer)?;
+        Ok(Partial(value))
+    }
+}
@@ -0,0 +1,51 @@
+use serde::de::{self, DeserializeSeed, Deserializer, Expected, IgnoredAny, SeqAccess, Visitor};
+use std::fmt;
+
+/// Deserializes a sequence in place into a fixed-size array
+///
+/// The elements are deserialized one by one into the array, without an intermediate `Vec`,
+/// and a sequence that has not the length of the array is rejected with an
+/// `invalid length` error that reports the number of elements of the sequence.
+/// It is used by the generated `Deserialize` implementations of the Simulink structures:
+/// ```ignore
+/// map.next_value_seed(ArraySeed(&mut inputs.SA_offsetF_cmd))?;
+/// ```
+pub struct ArraySeed<'a, T>(pub &'a mut [T]);
+
+/// Length of the array of an [ArraySeed]
+struct Length(usize);
+impl Expected for Length {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array of {} elements", self.0)
+    }
+}
+
+impl<'de, T: de::Deserialize<'de>> DeserializeSeed<'de> for ArraySeed<'_, T> {
+    type Value = ();
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: de::Deserialize<'de>> Visitor<'de> for ArraySeed<'_, T> {
+    type Value = ();
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Length(self.0.len()).fmt(formatter)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let n = self.0.len();
+        for (i, value) in self.0.iter_mut().enumerate() {
+            *value = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &Length(n)))?;
+        }
+        let mut extra = 0;
+        while seq.next_element::<IgnoredAny>()?.is_some() {
+            extra += 1;
+        }
+        if extra > 0 {
+            return Err(de::Error::invalid_length(n + extra, &Length(n)));
+        }
+        Ok(())
+    }
+}
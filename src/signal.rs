@@ -0,0 +1,63 @@
+use std::{error::Error, fmt::Display};
+
+/// Direction of a signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Input,
+    Output,
+}
+
+/// Description of a controller input or output signal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalInfo {
+    /// Simulink signal name
+    pub name: &'static str,
+    pub direction: Direction,
+    /// C data type
+    pub dtype: &'static str,
+    /// Number of values, 1 for a scalar
+    pub size: usize,
+    /// Byte offset of the signal in the inputs or outputs structure
+    pub offset: usize,
+    /// Simulink block path
+    pub block_path: Option<&'static str>,
+}
+
+/// Error of the signal accessors of the controllers
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SignalError {
+    /// The length of the data does not match the size of the signal
+    Length {
+        signal: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// The controller has no signal of that name
+    NotFound(String),
+    /// The signal is not a `f64` signal
+    Dtype {
+        signal: &'static str,
+        dtype: &'static str,
+    },
+}
+impl Display for SignalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignalError::Length {
+                signal,
+                expected,
+                found,
+            } => write!(
+                f,
+                "signal {} expects {} values, found {}",
+                signal, expected, found
+            ),
+            SignalError::NotFound(signal) => write!(f, "signal {} not found", signal),
+            SignalError::Dtype { signal, dtype } => {
+                write!(f, "signal {} is of type {}, not f64", signal, dtype)
+            }
+        }
+    }
+}
+impl Error for SignalError {}
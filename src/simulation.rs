@@ -0,0 +1,210 @@
+//! Batch simulation of the controllers
+//!
+//! A [Simulation] runs a controller over a sequence of input samples and records the outputs,
+//! and optionally the states, into flattened time series.
+//!
+//! # Example
+//! ```ignore
+//! let mut sim = Simulation::new(MySimulinkController::new()).record_states(true);
+//! // one row of `MySimulinkController::INPUTS_LEN` values per step
+//! let record = sim.run(data.chunks(MySimulinkController::INPUTS_LEN))?;
+//! let last_outputs = record.outputs.row(record.outputs.len() - 1);
+//! ```
+
+use crate::{Flatten, SignalError, SimulinkController};
+use std::cell::{Cell, RefCell};
+
+/// Input sample of a simulation
+///
+/// A sample is either an inputs structure, a slice of the flattened inputs,
+/// or one of them with a timestamp, e.g. `(t, inputs)`
+pub trait Sample<T> {
+    /// Returns the timestamp of the sample
+    fn time(&self) -> Option<f64> {
+        None
+    }
+    /// Writes the sample into the inputs
+    fn write(&self, inputs: &mut T) -> Result<(), SignalError>;
+}
+impl<T: Flatten + Clone> Sample<T> for T {
+    fn write(&self, inputs: &mut T) -> Result<(), SignalError> {
+        inputs.clone_from(self);
+        Ok(())
+    }
+}
+impl<T: Flatten> Sample<T> for &[f64] {
+    fn write(&self, inputs: &mut T) -> Result<(), SignalError> {
+        inputs.copy_from_slice(self)
+    }
+}
+impl<T: Flatten> Sample<T> for Vec<f64> {
+    fn write(&self, inputs: &mut T) -> Result<(), SignalError> {
+        inputs.copy_from_slice(self)
+    }
+}
+impl<T, S: Sample<T>> Sample<T> for (f64, S) {
+    fn time(&self) -> Option<f64> {
+        Some(self.0)
+    }
+    fn write(&self, inputs: &mut T) -> Result<(), SignalError> {
+        self.1.write(inputs)
+    }
+}
+
+/// Time series of flattened Simulink structures
+///
+/// The data is stored row-wise, with one row of [TimeSeries::width] values per time step
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TimeSeries {
+    /// Time steps
+    pub time: Vec<f64>,
+    /// Number of values per time step
+    pub width: usize,
+    /// Values
+    pub data: Vec<f64>,
+}
+impl TimeSeries {
+    /// Creates an empty time series with room for `capacity` time steps
+    pub fn with_capacity(width: usize, capacity: usize) -> Self {
+        Self {
+            time: Vec::with_capacity(capacity),
+            width,
+            data: Vec::with_capacity(width * capacity),
+        }
+    }
+    /// Returns the number of time steps
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+    /// Checks if the time series is empty
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+    /// Returns the values at time step `i`
+    pub fn row(&self, i: usize) -> &[f64] {
+        &self.data[i * self.width..(i + 1) * self.width]
+    }
+    /// Returns an iterator over the time steps and values
+    pub fn iter(&self) -> impl Iterator<Item = (f64, &[f64])> {
+        self.time
+            .iter()
+            .copied()
+            .zip(self.data.chunks(self.width.max(1)))
+    }
+    /// Appends the flattened structure at time `t`
+    ///
+    /// Returns an error, leaving the time series unchanged, if the width of the time series
+    /// is not the length of the flattened structure
+    pub fn push<T: Flatten>(&mut self, t: f64, value: &T) -> Result<(), SignalError> {
+        let n = self.data.len();
+        self.data.resize(n + self.width, 0.);
+        if let Err(e) = value.copy_to_slice(&mut self.data[n..]) {
+            self.data.truncate(n);
+            return Err(e);
+        }
+        self.time.push(t);
+        Ok(())
+    }
+}
+
+/// Record of a simulation
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Record {
+    /// Outputs time series
+    pub outputs: TimeSeries,
+    /// States time series, if recorded
+    pub states: Option<TimeSeries>,
+}
+
+/// Batch simulation of a controller
+#[derive(Debug, Clone)]
+pub struct Simulation<C> {
+    controller: C,
+    record_states: bool,
+}
+impl<C: SimulinkController> Simulation<C> {
+    /// Creates a new simulation of the controller
+    pub fn new(controller: C) -> Self {
+        Self {
+            controller,
+            record_states: false,
+        }
+    }
+    /// Records the states in addition to the outputs
+    pub fn record_states(mut self, record_states: bool) -> Self {
+        self.record_states = record_states;
+        self
+    }
+    /// Returns the controller
+    pub fn controller(&self) -> &C {
+        &self.controller
+    }
+    /// Returns the controller for writing, e.g. to reset it
+    pub fn controller_mut(&mut self) -> &mut C {
+        &mut self.controller
+    }
+    /// Consumes the simulation and returns the controller
+    pub fn into_controller(self) -> C {
+        self.controller
+    }
+    /// Runs the controller for each input sample and records the outputs
+    ///
+    /// The outputs are recorded at the timestamps of the samples, if any,
+    /// or else at the multiples of the sample time of the controller, or at the step indices
+    /// if the sample time is unknown.
+    /// The samples are streamed: each sample is written into the inputs and the controller
+    /// is stepped as the sample is pulled from the iterator, the real-time model structure
+    /// of the C code being built once for the whole run, see [SimulinkController::run_while].
+    /// An invalid sample stops the run with an error, the controller having been stepped
+    /// with the previous samples
+    pub fn run<I>(&mut self, samples: I) -> Result<Record, SignalError>
+    where
+        I: IntoIterator,
+        I::Item: Sample<C::Inputs>,
+    {
+        let dt = C::sample_time().unwrap_or(1.);
+        let mut samples = samples.into_iter();
+        // the records are preallocated only if the number of samples is known
+        let n = match samples.size_hint() {
+            (lower, Some(upper)) if lower == upper => lower,
+            _ => 0,
+        };
+        let mut outputs = TimeSeries::with_capacity(<C::Outputs as Flatten>::LEN, n);
+        let mut states = self
+            .record_states
+            .then(|| TimeSeries::with_capacity(<C::States as Flatten>::LEN, n));
+        // the time of the current sample, and the first error, shared by `before` and `after`
+        let time = Cell::new(0.);
+        let error = RefCell::new(None);
+        self.controller.run_while(
+            |i, inputs| {
+                if error.borrow().is_some() {
+                    return false;
+                }
+                let Some(sample) = samples.next() else {
+                    return false;
+                };
+                time.set(sample.time().unwrap_or(i as f64 * dt));
+                sample
+                    .write(inputs)
+                    .map_err(|e| error.replace(Some(e)))
+                    .is_ok()
+            },
+            |_, y, x| {
+                let recorded = outputs
+                    .push(time.get(), y)
+                    .and_then(|_| match states.as_mut() {
+                        Some(states) => states.push(time.get(), x),
+                        None => Ok(()),
+                    });
+                if let Err(e) = recorded {
+                    error.replace(Some(e));
+                }
+            },
+        );
+        match error.into_inner() {
+            Some(e) => Err(e),
+            None => Ok(Record { outputs, states }),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Checkpoints of the controllers
+//!
+//! A [Snapshot] holds the states of a controller, and optionally its inputs and outputs,
+//! together with the model name, the model version and the hash of the model interface,
+//! so that a long simulation can be resumed from a checkpoint written to disk.
+//! A snapshot is only restored into a controller of the same model with the same interface.
+//!
+//! # Example
+//! ```ignore
+//! let snapshot = controller.snapshot();
+//! snapshot.write("checkpoint.json")?;
+//! // later on
+//! let mut controller = MySimulinkController::new();
+//! controller.restore(&Snapshot::read("checkpoint.json")?)?;
+//! ```
+
+use crate::{Flatten, SimulinkController};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{error::Error, fmt::Display, fs, io, path::Path};
+
+/// Version of the snapshot format
+pub const SNAPSHOT_FORMAT: u32 = 1;
+
+/// Checkpoint of a controller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "C::Inputs: Serialize, C::Outputs: Serialize, C::States: Serialize",
+    deserialize = "C::Inputs: Deserialize<'de>, C::Outputs: Deserialize<'de>, C::States: Deserialize<'de>"
+))]
+pub struct Snapshot<C: SimulinkController> {
+    /// Version of the snapshot format
+    pub format: u32,
+    /// Simulink model name
+    pub model: String,
+    /// Simulink model version, for information only, it is not checked
+    pub model_version: Option<String>,
+    /// Hash of the model interface
+    pub interface_hash: u64,
+    /// States Simulink structure
+    pub states: C::States,
+    /// Inputs Simulink structure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<C::Inputs>,
+    /// Outputs Simulink structure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<C::Outputs>,
+}
+
+impl<C: SimulinkController> Snapshot<C> {
+    /// Creates a new snapshot of the states of the controller `C`
+    pub fn new(states: C::States) -> Self {
+        let info = C::info();
+        Self {
+            format: SNAPSHOT_FORMAT,
+            model: info.name.to_string(),
+            model_version: info.model_version.map(|version| version.to_string()),
+            interface_hash: info.interface_hash,
+            states,
+            inputs: None,
+            outputs: None,
+        }
+    }
+    /// Adds the inputs to the snapshot
+    pub fn inputs(mut self, inputs: C::Inputs) -> Self {
+        self.inputs = Some(inputs);
+        self
+    }
+    /// Adds the outputs to the snapshot
+    pub fn outputs(mut self, outputs: C::Outputs) -> Self {
+        self.outputs = Some(outputs);
+        self
+    }
+    /// Checks that the snapshot can be restored into the controller `C`
+    pub fn check(&self) -> Result<(), SnapshotError> {
+        let info = C::info();
+        if self.format != SNAPSHOT_FORMAT {
+            return Err(SnapshotError::Format {
+                expected: SNAPSHOT_FORMAT,
+                found: self.format,
+            });
+        }
+        if self.model != info.name {
+            return Err(SnapshotError::Model {
+                expected: info.name,
+                found: self.model.clone(),
+            });
+        }
+        if self.interface_hash != info.interface_hash {
+            return Err(SnapshotError::Interface {
+                model: info.name,
+                expected: info.interface_hash,
+                found: self.interface_hash,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<C> Snapshot<C>
+where
+    C: SimulinkController,
+    C::Inputs: Serialize + DeserializeOwned,
+    C::Outputs: Serialize + DeserializeOwned,
+    C::States: Serialize + DeserializeOwned,
+{
+    /// Reads the snapshot from a JSON file
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let mut value: Value = serde_json::from_str(&contents).map_err(invalid_data)?;
+        let non_finite: Map<String, Value> = match value
+            .as_object_mut()
+            .and_then(|snapshot| snapshot.remove(NON_FINITE))
+        {
+            Some(non_finite) => serde_json::from_value(non_finite).map_err(invalid_data)?,
+            None => Map::new(),
+        };
+        // the non-finite values are written as null, set to 0 until they are restored
+        let mut entries = |group: &str| -> io::Result<Vec<(usize, String)>> {
+            let entries: Vec<(usize, String)> = match non_finite.get(group) {
+                Some(entries) => serde_json::from_value(entries.clone()).map_err(invalid_data)?,
+                None => vec![],
+            };
+            let nulls = value.get_mut(group).map_or(0, zero_nulls);
+            if nulls != entries.len() {
+                return Err(invalid_data(format!(
+                    "snapshot has {} null values in the {}, but {} non-finite values",
+                    nulls,
+                    group,
+                    entries.len()
+                )));
+            }
+            Ok(entries)
+        };
+        let (states, inputs, outputs) =
+            (entries("states")?, entries("inputs")?, entries("outputs")?);
+        let mut snapshot: Self = serde_json::from_value(value).map_err(invalid_data)?;
+        set_non_finite(&mut snapshot.states, &states)?;
+        if let Some(snapshot_inputs) = snapshot.inputs.as_mut() {
+            set_non_finite(snapshot_inputs, &inputs)?;
+        }
+        if let Some(snapshot_outputs) = snapshot.outputs.as_mut() {
+            set_non_finite(snapshot_outputs, &outputs)?;
+        }
+        Ok(snapshot)
+    }
+    /// Writes the snapshot to a JSON file
+    ///
+    /// JSON has no NaN nor infinite numbers, so the non-finite values are written as null
+    /// and listed with their index in the flattened structures under the `non_finite` key
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut value = serde_json::to_value(self).map_err(invalid_data)?;
+        let mut non_finite = Map::new();
+        for (group, data) in ["states", "inputs", "outputs"].into_iter().zip([
+            Some(self.states.to_vec()),
+            self.inputs.as_ref().map(Flatten::to_vec),
+            self.outputs.as_ref().map(Flatten::to_vec),
+        ]) {
+            let entries: Vec<Value> = data
+                .unwrap_or_default()
+                .into_iter()
+                .enumerate()
+                .filter(|(_, x)| !x.is_finite())
+                .map(|(i, x)| json!([i, x.to_string()]))
+                .collect();
+            if !entries.is_empty() {
+                non_finite.insert(group.to_string(), Value::Array(entries));
+            }
+        }
+        if let (Some(snapshot), false) = (value.as_object_mut(), non_finite.is_empty()) {
+            snapshot.insert(NON_FINITE.to_string(), Value::Object(non_finite));
+        }
+        let contents = serde_json::to_string(&value).map_err(invalid_data)?;
+        fs::write(path, contents)
+    }
+}
+
+/// Key of the non-finite values in a snapshot file
+const NON_FINITE: &str = "non_finite";
+
+fn invalid_data<E: Into<Box<dyn Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Replaces the null values by 0 and returns their number
+fn zero_nulls(value: &mut Value) -> usize {
+    match value {
+        Value::Null => {
+            *value = json!(0.0);
+            1
+        }
+        Value::Array(values) => values.iter_mut().map(zero_nulls).sum(),
+        Value::Object(values) => values.values_mut().map(zero_nulls).sum(),
+        _ => 0,
+    }
+}
+
+/// Sets the non-finite values of a structure given by their index in the flattened structure
+fn set_non_finite<T: Flatten>(structure: &mut T, entries: &[(usize, String)]) -> io::Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut data = structure.to_vec();
+    for (i, x) in entries {
+        let n = data.len();
+        *data.get_mut(*i).ok_or_else(|| {
+            invalid_data(format!("non-finite value at {} is out of {} values", i, n))
+        })? = x
+            .parse()
+            .map_err(|_| invalid_data(format!("invalid non-finite value {:?}", x)))?;
+    }
+    structure.copy_from_slice(&data).map_err(invalid_data)
+}
+
+/// Error of restoring a [Snapshot]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SnapshotError {
+    /// The snapshot has been written with another version of the snapshot format
+    Format { expected: u32, found: u32 },
+    /// The snapshot has been taken with another model
+    Model {
+        expected: &'static str,
+        found: String,
+    },
+    /// The snapshot has been taken with another interface of the model
+    Interface {
+        model: &'static str,
+        expected: u64,
+        found: u64,
+    },
+}
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Format { expected, found } => write!(
+                f,
+                "snapshot format version {} is not supported, expected {}",
+                found, expected
+            ),
+            SnapshotError::Model { expected, found } => write!(
+                f,
+                "snapshot of model {} cannot be restored into model {}",
+                found, expected
+            ),
+            SnapshotError::Interface {
+                model,
+                expected,
+                found,
+            } => write!(
+                f,
+                "snapshot of model {} has interface hash {:016x}, expected {:016x}",
+                model, found, expected
+            ),
+        }
+    }
+}
+impl Error for SnapshotError {}
@@ -0,0 +1,328 @@
+//! Golden-vector regression testing
+//!
+//! A [Golden] test replays the inputs recorded from a Simulink simulation through a controller
+//! and compares, step by step, the outputs of the controller with the recorded outputs.
+//! The inputs and outputs [Trace]s are keyed by the Simulink or snake case signal names,
+//! the inputs that are not in the trace keep their current values.
+//!
+//! # Example
+//! ```ignore
+//! let golden = Golden::read("tests/golden/inputs.json", "tests/golden/outputs.json")?
+//!     .abs(1e-9)
+//!     .signal_tolerance("Res_Act_F", Tolerance::new(1e-6, 1e-9));
+//! golden.check(&mut MySimulinkController::new())?;
+//! ```
+//! or, with the [golden_test](crate::golden_test) macro,
+//! ```ignore
+//! simulink_rs::golden_test!(
+//!     golden,
+//!     MySimulinkController,
+//!     "tests/golden/inputs.json",
+//!     "tests/golden/outputs.json",
+//!     abs = 1e-9,
+//! );
+//! ```
+
+use crate::{
+    trace::{Trace, TraceSignal},
+    SignalError, SimulinkController,
+};
+use simulink_model::snake_case;
+use std::{error::Error, fmt::Display, io, path::Path};
+
+/// Absolute and relative tolerances of the comparison of two values
+///
+/// `found` matches `expected` if `|found - expected| <= abs + rel * |expected|`,
+/// the default tolerance only accepts equal values
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// Absolute tolerance
+    pub abs: f64,
+    /// Relative tolerance
+    pub rel: f64,
+}
+impl Tolerance {
+    /// Creates a new tolerance
+    pub fn new(abs: f64, rel: f64) -> Self {
+        Self { abs, rel }
+    }
+    /// Checks if `found` matches `expected` within the tolerance
+    ///
+    /// NaN only matches NaN
+    pub fn accepts(&self, expected: f64, found: f64) -> bool {
+        if expected.is_nan() || found.is_nan() {
+            return expected.is_nan() && found.is_nan();
+        }
+        expected == found || (found - expected).abs() <= self.abs + self.rel * expected.abs()
+    }
+}
+
+/// First difference between the outputs of a controller and the recorded outputs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    /// Time step index
+    pub step: usize,
+    /// Time of the step
+    pub time: f64,
+    /// Output signal name
+    pub signal: String,
+    /// Index of the diverging element of the signal
+    pub element: usize,
+    /// Recorded value
+    pub expected: f64,
+    /// Value of the controller
+    pub found: f64,
+    /// Tolerance of the signal
+    pub tolerance: Tolerance,
+}
+impl Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}] diverges at step {} (t = {}): expected {:e}, found {:e}, abs. error {:e} (abs: {:e}, rel: {:e})",
+            self.signal,
+            self.element,
+            self.step,
+            self.time,
+            self.expected,
+            self.found,
+            (self.found - self.expected).abs(),
+            self.tolerance.abs,
+            self.tolerance.rel
+        )
+    }
+}
+
+/// Error of a [Golden] test
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum GoldenError {
+    /// The inputs and outputs traces do not have the same number of time steps
+    Steps { inputs: usize, outputs: usize },
+    /// A recorded signal does not have one row of values per time step
+    Length {
+        signal: String,
+        expected: usize,
+        found: usize,
+    },
+    /// A signal of the traces cannot be set or read
+    Signal(SignalError),
+    /// A recorded output signal is not a `f64` signal of the controller
+    Dtype { signal: String, dtype: &'static str },
+    /// A recorded output signal does not have the size of the controller signal
+    Width {
+        signal: String,
+        expected: usize,
+        found: usize,
+    },
+    /// The outputs of the controller differ from the recorded outputs
+    Divergence(Divergence),
+}
+impl Display for GoldenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GoldenError::Steps { inputs, outputs } => write!(
+                f,
+                "the inputs trace has {} time steps but the outputs trace has {}",
+                inputs, outputs
+            ),
+            GoldenError::Length {
+                signal,
+                expected,
+                found,
+            } => write!(
+                f,
+                "recorded signal {} has {} values, expected {}",
+                signal, found, expected
+            ),
+            GoldenError::Signal(e) => e.fmt(f),
+            GoldenError::Dtype { signal, dtype } => write!(
+                f,
+                "recorded signal {} is of type {} in the controller, not f64",
+                signal, dtype
+            ),
+            GoldenError::Width {
+                signal,
+                expected,
+                found,
+            } => write!(
+                f,
+                "recorded signal {} has {} values per step, expected {}",
+                signal, found, expected
+            ),
+            GoldenError::Divergence(divergence) => divergence.fmt(f),
+        }
+    }
+}
+impl Error for GoldenError {}
+impl From<SignalError> for GoldenError {
+    fn from(e: SignalError) -> Self {
+        GoldenError::Signal(e)
+    }
+}
+
+/// Golden-vector test of a controller against recorded inputs and outputs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Golden {
+    inputs: Trace,
+    outputs: Trace,
+    tolerance: Tolerance,
+    tolerances: Vec<(String, Tolerance)>,
+}
+impl Golden {
+    /// Creates a new golden test from the recorded inputs and outputs
+    ///
+    /// The traces must have the same number of time steps,
+    /// and every signal one row of values per time step
+    pub fn new(inputs: Trace, outputs: Trace) -> Result<Self, GoldenError> {
+        if inputs.len() != outputs.len() {
+            return Err(GoldenError::Steps {
+                inputs: inputs.len(),
+                outputs: outputs.len(),
+            });
+        }
+        let steps = outputs.len();
+        if let Some(signal) = inputs
+            .signals
+            .iter()
+            .chain(&outputs.signals)
+            .find(|signal| signal.data.len() != signal.width * steps)
+        {
+            return Err(GoldenError::Length {
+                signal: signal.name.clone(),
+                expected: signal.width * steps,
+                found: signal.data.len(),
+            });
+        }
+        Ok(Self {
+            inputs,
+            outputs,
+            tolerance: Default::default(),
+            tolerances: vec![],
+        })
+    }
+    /// Reads the recorded inputs and outputs from JSON [Trace] files
+    pub fn read<P: AsRef<Path>, Q: AsRef<Path>>(inputs: P, outputs: Q) -> io::Result<Self> {
+        Self::new(Trace::read(inputs)?, Trace::read(outputs)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    /// Sets the tolerance of all the output signals
+    pub fn tolerance(mut self, tolerance: Tolerance) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+    /// Sets the absolute tolerance of all the output signals
+    pub fn abs(mut self, abs: f64) -> Self {
+        self.tolerance.abs = abs;
+        self
+    }
+    /// Sets the relative tolerance of all the output signals
+    pub fn rel(mut self, rel: f64) -> Self {
+        self.tolerance.rel = rel;
+        self
+    }
+    /// Sets the tolerance of an output signal
+    pub fn signal_tolerance<S: Into<String>>(mut self, signal: S, tolerance: Tolerance) -> Self {
+        self.tolerances.push((signal.into(), tolerance));
+        self
+    }
+    /// Returns the tolerance of an output signal
+    fn tolerance_of(&self, signal: &str) -> Tolerance {
+        self.tolerances
+            .iter()
+            .rev()
+            .find(|(name, _)| name == signal)
+            .map_or(self.tolerance, |(_, tolerance)| *tolerance)
+    }
+    /// Replays the recorded inputs through the controller and compares the outputs
+    ///
+    /// The outputs are compared after each step, and the first divergence is returned
+    pub fn check<C: SimulinkController>(&self, controller: &mut C) -> Result<(), GoldenError> {
+        for (step, &time) in self.outputs.time.iter().enumerate() {
+            for signal in &self.inputs.signals {
+                controller.set(&signal.name, signal.row(step))?;
+            }
+            controller.step();
+            for signal in &self.outputs.signals {
+                let Some(found) = controller.get(&signal.name) else {
+                    return Err(missing(controller, signal));
+                };
+                if found.len() != signal.width {
+                    return Err(GoldenError::Width {
+                        signal: signal.name.clone(),
+                        expected: found.len(),
+                        found: signal.width,
+                    });
+                }
+                let tolerance = self.tolerance_of(&signal.name);
+                if let Some((element, (&expected, &found))) = signal
+                    .row(step)
+                    .iter()
+                    .zip(found)
+                    .enumerate()
+                    .find(|(_, (&expected, &found))| !tolerance.accepts(expected, found))
+                {
+                    return Err(GoldenError::Divergence(Divergence {
+                        step,
+                        time,
+                        signal: signal.name.clone(),
+                        element,
+                        expected,
+                        found,
+                        tolerance,
+                    }));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the error of an output signal that cannot be read from the controller
+fn missing<C: SimulinkController>(controller: &C, signal: &TraceSignal) -> GoldenError {
+    match controller
+        .signals()
+        .iter()
+        .find(|info| info.name == signal.name || snake_case(info.name) == signal.name)
+    {
+        Some(info) => GoldenError::Dtype {
+            signal: signal.name.clone(),
+            dtype: info.dtype,
+        },
+        None => SignalError::NotFound(signal.name.clone()).into(),
+    }
+}
+
+/// Generates a golden-vector test of a controller
+///
+/// The test reads the recorded inputs and outputs from JSON [Trace] files,
+/// with paths relative to the crate root, and fails on the first divergence.
+/// The options are the setters of [Golden] that take a single value, e.g. `abs = 1e-9`
+/// or `rel = 1e-6`.
+///
+/// # Example
+/// ```ignore
+/// simulink_rs::golden_test!(
+///     golden,
+///     MySimulinkController,
+///     "tests/golden/inputs.json",
+///     "tests/golden/outputs.json",
+///     abs = 1e-9,
+///     rel = 1e-6,
+/// );
+/// ```
+#[macro_export]
+macro_rules! golden_test {
+    ($name:ident, $controller:ty, $inputs:expr, $outputs:expr $(, $option:ident = $value:expr)* $(,)?) => {
+        #[test]
+        fn $name() {
+            let golden = $crate::testing::Golden::read($inputs, $outputs)
+                .unwrap_or_else(|e| panic!("failed to read the golden traces: {}", e))
+                $(.$option($value))*;
+            let mut controller = <$controller as $crate::SimulinkController>::new();
+            if let Err(e) = golden.check(&mut controller) {
+                panic!("{}", e);
+            }
+        }
+    };
+}
@@ -0,0 +1,142 @@
+//! Recorded time series of signals
+//!
+//! A [Trace] holds the time steps and the values of some signals keyed by name,
+//! e.g. the inputs and outputs logged from a Simulink simulation.
+//! A trace is written to and read from JSON:
+//! ```json
+//! {
+//!   "time": [0.0, 0.001],
+//!   "signals": [
+//!     { "name": "u", "width": 3, "data": [1.0, 2.0, 3.0, 4.0, 5.0, 6.0] }
+//!   ]
+//! }
+//! ```
+//!
+//! For the test benches, the values of some signals at each time step are also streamed,
+//! one [Record] per step, from and to CSV files, with one column per array element,
+//! e.g. `SA_offsetF_cmd[12]`, and JSON-lines files, with one JSON object per line.
+//! The records are mapped onto the inputs and outputs structures of the controllers
+//! with their serde implementations:
+//! ```ignore
+//! use simulink_rs::trace::{CsvReader, JsonLinesWriter, Record};
+//!
+//! let inputs = CsvReader::open("inputs.csv")?.deserialize::<ExtU_M1SA_Control_OA_T>();
+//! let mut writer = JsonLinesWriter::create("outputs.jsonl")?.select(&["Res_Act_F"])?;
+//! for u in inputs {
+//!     controller.inputs = u?;
+//!     controller.step();
+//!     writer.serialize(&controller.outputs)?;
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::{fs, io, path::Path};
+
+mod csv;
+mod jsonl;
+mod record;
+pub use csv::{CsvReader, CsvWriter};
+pub use jsonl::{JsonLinesReader, JsonLinesWriter};
+pub use record::{Record, Values};
+
+/// Values of a signal in a [Trace]
+///
+/// The data is stored row-wise, with one row of [TraceSignal::width] values per time step
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceSignal {
+    /// Simulink or snake case signal name
+    pub name: String,
+    /// Number of values per time step, 1 for a scalar
+    pub width: usize,
+    /// Values
+    pub data: Vec<f64>,
+}
+impl TraceSignal {
+    /// Creates a new signal
+    pub fn new<S: Into<String>>(name: S, width: usize, data: Vec<f64>) -> Self {
+        Self {
+            name: name.into(),
+            width,
+            data,
+        }
+    }
+    /// Returns the number of time steps
+    pub fn len(&self) -> usize {
+        self.data.len() / self.width.max(1)
+    }
+    /// Checks if the signal is empty
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+    /// Returns the values at time step `i`
+    pub fn row(&self, i: usize) -> &[f64] {
+        &self.data[i * self.width..(i + 1) * self.width]
+    }
+}
+
+/// Time series of signals keyed by name
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trace {
+    /// Time steps
+    pub time: Vec<f64>,
+    /// Signals, in order
+    pub signals: Vec<TraceSignal>,
+}
+impl Trace {
+    /// Creates a new trace without signals
+    pub fn new(time: Vec<f64>) -> Self {
+        Self {
+            time,
+            signals: vec![],
+        }
+    }
+    /// Adds a signal to the trace
+    pub fn signal<S: Into<String>>(mut self, name: S, width: usize, data: Vec<f64>) -> Self {
+        self.signals.push(TraceSignal::new(name, width, data));
+        self
+    }
+    /// Returns the number of time steps
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+    /// Checks if the trace is empty
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+    /// Returns the signal of a given name
+    pub fn get(&self, name: &str) -> Option<&TraceSignal> {
+        self.signals.iter().find(|signal| signal.name == name)
+    }
+    /// Checks that every signal has one row of values per time step
+    pub fn validate(&self) -> io::Result<()> {
+        for signal in &self.signals {
+            if signal.data.len() != signal.width * self.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "signal {} has {} values, expected {} time steps of {} values",
+                        signal.name,
+                        signal.data.len(),
+                        self.len(),
+                        signal.width
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// Reads the trace from a JSON file
+    pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let trace: Self = serde_json::from_str(&contents)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        trace.validate()?;
+        Ok(trace)
+    }
+    /// Writes the trace to a JSON file
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let contents = serde_json::to_string(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path, contents)
+    }
+}
@@ -0,0 +1,284 @@
+//! CSV reader and writer of records
+//!
+//! The first line is the header, with one column per scalar signal, named after the signal,
+//! and one column per element of an array signal, e.g. `SA_offsetF_cmd[12]`,
+//! followed by one line per record.
+//! The fields are never quoted, a quoted field is an error
+
+use super::record::{check_selection, is_selected, selection, Record, Values};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+/// Splits a CSV line into its fields
+///
+/// The fields are not quoted, as neither the signal names nor the numbers need quoting,
+/// so a quoted field is an error rather than being split on its commas
+fn fields(line: &str) -> Result<Vec<&str>, String> {
+    if line.contains('"') {
+        return Err("quoted CSV fields are not supported".to_string());
+    }
+    Ok(line.split(',').map(str::trim).collect())
+}
+
+/// Columns of a signal
+#[derive(Debug, Clone, PartialEq)]
+struct Column {
+    name: String,
+    scalar: bool,
+    /// Indices of the columns of the signal elements
+    indices: Vec<usize>,
+}
+
+/// Streaming CSV reader of records
+///
+/// Each line is read as a [Record], with the signals of the header
+#[derive(Debug)]
+pub struct CsvReader<R> {
+    lines: io::Lines<R>,
+    columns: Vec<Column>,
+    line: usize,
+}
+impl CsvReader<BufReader<File>> {
+    /// Opens a CSV file
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+impl<R: BufRead> CsvReader<R> {
+    /// Creates a CSV reader, reading the header
+    pub fn new(reader: R) -> io::Result<Self> {
+        let mut lines = reader.lines();
+        let header = lines
+            .next()
+            .ok_or_else(|| invalid_data("the CSV header is missing"))??;
+        let mut columns: Vec<(&str, bool, Vec<Option<usize>>)> = vec![];
+        let labels = fields(&header).map_err(|e| invalid_data(format!("CSV header: {}", e)))?;
+        for (index, label) in labels.into_iter().enumerate() {
+            let element = label
+                .strip_suffix(']')
+                .and_then(|label| label.rsplit_once('['))
+                .and_then(|(name, i)| Some((name, i.parse::<usize>().ok()?)));
+            let (name, scalar, i) = match element {
+                Some((name, i)) => (name, false, i),
+                None => (label, true, 0),
+            };
+            let k = match columns.iter().position(|column| column.0 == name) {
+                Some(k) if !scalar && !columns[k].1 => k,
+                Some(_) => return Err(invalid_data(format!("duplicate CSV column {}", label))),
+                None => {
+                    columns.push((name, scalar, vec![]));
+                    columns.len() - 1
+                }
+            };
+            let indices = &mut columns[k].2;
+            if indices.len() <= i {
+                indices.resize(i + 1, None);
+            }
+            if indices[i].replace(index).is_some() {
+                return Err(invalid_data(format!("duplicate CSV column {}", label)));
+            }
+        }
+        let columns = columns
+            .into_iter()
+            .map(|(name, scalar, indices)| {
+                let indices = indices
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, index)| {
+                        index.ok_or_else(|| {
+                            invalid_data(format!("CSV column {}[{}] is missing", name, i))
+                        })
+                    })
+                    .collect::<io::Result<_>>()?;
+                Ok(Column {
+                    name: name.to_string(),
+                    scalar,
+                    indices,
+                })
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Self {
+            lines,
+            columns,
+            line: 1,
+        })
+    }
+    /// Reads only some signals, given by their Simulink or snake case names
+    ///
+    /// The records only have the selected signals, so a structure is deserialized
+    /// from a selection with [Partial](crate::Partial), unless all its fields are selected
+    pub fn select(mut self, names: &[&str]) -> io::Result<Self> {
+        let selection = selection(names)?;
+        check_selection(
+            &selection,
+            self.columns.iter().map(|column| column.name.as_str()),
+            "CSV header",
+        )?;
+        self.columns
+            .retain(|column| is_selected(&selection, &column.name));
+        Ok(self)
+    }
+    /// Returns the names of the signals that are read
+    pub fn signals(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|column| column.name.as_str())
+    }
+    /// Maps each record onto a structure, e.g. the inputs of a controller
+    pub fn deserialize<T: DeserializeOwned>(self) -> impl Iterator<Item = io::Result<T>> {
+        self.map(|record| record?.deserialize())
+    }
+}
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = io::Result<Record>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = loop {
+            match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => self.line += 1,
+                Ok(line) => break line,
+                Err(e) => return Some(Err(e)),
+            }
+        };
+        self.line += 1;
+        let fields = match fields(&line) {
+            Ok(fields) => fields,
+            Err(e) => return Some(Err(invalid_data(format!("line {}: {}", self.line, e)))),
+        };
+        let value = |index: usize| {
+            let field = fields.get(index).ok_or_else(|| {
+                invalid_data(format!(
+                    "line {} has only {} columns",
+                    self.line,
+                    fields.len()
+                ))
+            })?;
+            field.parse::<f64>().map_err(|_| {
+                invalid_data(format!("line {}: invalid number {:?}", self.line, field))
+            })
+        };
+        let signals = self
+            .columns
+            .iter()
+            .map(|column| {
+                let values = if column.scalar {
+                    Values::Scalar(value(column.indices[0])?)
+                } else {
+                    Values::Array(
+                        column
+                            .indices
+                            .iter()
+                            .map(|&index| value(index))
+                            .collect::<io::Result<_>>()?,
+                    )
+                };
+                Ok((column.name.clone(), values))
+            })
+            .collect::<io::Result<_>>();
+        Some(signals.map(|signals| Record { signals }))
+    }
+}
+
+/// Streaming CSV writer of records
+///
+/// The header is written with the first record, the next records must have the same signals
+#[derive(Debug)]
+pub struct CsvWriter<W: Write> {
+    writer: W,
+    selection: Vec<String>,
+    layout: Option<Vec<(String, Option<usize>)>>,
+}
+impl CsvWriter<BufWriter<File>> {
+    /// Creates a CSV file
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+impl<W: Write> CsvWriter<W> {
+    /// Creates a CSV writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            selection: vec![],
+            layout: None,
+        }
+    }
+    /// Writes only some signals, given by their Simulink or snake case names
+    ///
+    /// Writing a record without one of the selected signals fails
+    pub fn select(mut self, names: &[&str]) -> io::Result<Self> {
+        self.selection = selection(names)?;
+        Ok(self)
+    }
+    /// Writes a record
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        check_selection(
+            &self.selection,
+            record.signals.iter().map(|(name, _)| name.as_str()),
+            "record",
+        )?;
+        let signals: Vec<_> = record
+            .signals
+            .iter()
+            .filter(|(name, _)| is_selected(&self.selection, name))
+            .collect();
+        let layout: Vec<_> = signals
+            .iter()
+            .map(|(name, values)| {
+                let width = match values {
+                    Values::Scalar(_) => None,
+                    Values::Array(values) => Some(values.len()),
+                };
+                (name.clone(), width)
+            })
+            .collect();
+        match &self.layout {
+            Some(header) if *header != layout => {
+                return Err(invalid_data(
+                    "the signals of the record do not match the CSV header",
+                ))
+            }
+            Some(_) => (),
+            None => {
+                if let Some((name, _)) = layout.iter().find(|(name, _)| name.contains([',', '"'])) {
+                    return Err(invalid_data(format!(
+                        "signal {:?} cannot be written to a CSV header",
+                        name
+                    )));
+                }
+                let header: Vec<String> = layout
+                    .iter()
+                    .flat_map(|(name, width)| match width {
+                        Some(width) => (0..*width).map(|i| format!("{}[{}]", name, i)).collect(),
+                        None => vec![name.clone()],
+                    })
+                    .collect();
+                writeln!(self.writer, "{}", header.join(","))?;
+                self.layout = Some(layout);
+            }
+        }
+        let line: Vec<String> = signals
+            .iter()
+            .flat_map(|(_, values)| values.as_slice())
+            .map(|value| value.to_string())
+            .collect();
+        writeln!(self.writer, "{}", line.join(","))
+    }
+    /// Writes the fields of a structure, e.g. the outputs of a controller, as a record
+    pub fn serialize<T: Serialize>(&mut self, value: &T) -> io::Result<()> {
+        self.write(&Record::from_serialize(value)?)
+    }
+    /// Flushes the writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
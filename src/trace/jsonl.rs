@@ -0,0 +1,138 @@
+//! JSON-lines reader and writer of records
+//!
+//! Each line is a JSON object, with one field per signal, e.g.
+//! ```json
+//! {"LC_FxyzMxyz_CG":[1.0,0.0,0.0,0.0,0.0,0.0],"time":0.001}
+//! ```
+//! NaN and infinite values are written as `null` and read back as NaN
+
+use super::record::{selection, Record, Values};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+/// Streaming JSON-lines reader of records
+#[derive(Debug)]
+pub struct JsonLinesReader<R> {
+    lines: io::Lines<R>,
+    selection: Vec<String>,
+    line: usize,
+}
+impl JsonLinesReader<BufReader<File>> {
+    /// Opens a JSON-lines file
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(BufReader::new(File::open(path)?)))
+    }
+}
+impl<R: BufRead> JsonLinesReader<R> {
+    /// Creates a JSON-lines reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: reader.lines(),
+            selection: vec![],
+            line: 0,
+        }
+    }
+    /// Reads only some signals, given by their Simulink or snake case names
+    ///
+    /// The records only have the selected signals, so a structure is deserialized
+    /// from a selection with [Partial](crate::Partial), unless all its fields are selected.
+    /// Reading a line without one of the selected signals fails
+    pub fn select(mut self, names: &[&str]) -> io::Result<Self> {
+        self.selection = selection(names)?;
+        Ok(self)
+    }
+    /// Maps each record onto a structure, e.g. the inputs of a controller
+    pub fn deserialize<T: DeserializeOwned>(self) -> impl Iterator<Item = io::Result<T>> {
+        self.map(|record| record?.deserialize())
+    }
+}
+impl<R: BufRead> Iterator for JsonLinesReader<R> {
+    type Item = io::Result<Record>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = loop {
+            self.line += 1;
+            match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => break line,
+                Err(e) => return Some(Err(e)),
+            }
+        };
+        let record = serde_json::from_str::<Map<String, Value>>(&line)
+            .map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", self.line, e),
+                )
+            })
+            .and_then(Record::from_json)
+            .and_then(|mut record| {
+                record.retain(&self.selection, &format!("line {}", self.line))?;
+                Ok(record)
+            });
+        Some(record)
+    }
+}
+
+/// Streaming JSON-lines writer of records
+#[derive(Debug)]
+pub struct JsonLinesWriter<W: Write> {
+    writer: W,
+    selection: Vec<String>,
+}
+impl JsonLinesWriter<BufWriter<File>> {
+    /// Creates a JSON-lines file
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+impl<W: Write> JsonLinesWriter<W> {
+    /// Creates a JSON-lines writer
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            selection: vec![],
+        }
+    }
+    /// Writes only some signals, given by their Simulink or snake case names
+    ///
+    /// Writing a record without one of the selected signals fails
+    pub fn select(mut self, names: &[&str]) -> io::Result<Self> {
+        self.selection = selection(names)?;
+        Ok(self)
+    }
+    /// Writes a record
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        let mut record = record.clone();
+        record.retain(&self.selection, "record")?;
+        let object: Map<String, Value> = record
+            .signals
+            .iter()
+            .map(|(name, values)| {
+                let values = match values {
+                    Values::Scalar(value) => Value::from(*value),
+                    Values::Array(values) => Value::from(values.as_slice()),
+                };
+                (name.clone(), values)
+            })
+            .collect();
+        serde_json::to_writer(&mut self.writer, &object)?;
+        writeln!(self.writer)
+    }
+    /// Writes the fields of a structure, e.g. the outputs of a controller, as a record
+    pub fn serialize<T: Serialize>(&mut self, value: &T) -> io::Result<()> {
+        self.write(&Record::from_serialize(value)?)
+    }
+    /// Flushes the writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -0,0 +1,619 @@
+//! Values of some signals at one time step
+
+use serde::{
+    de::{
+        self,
+        value::{Error, MapDeserializer, SeqDeserializer},
+        DeserializeOwned, IntoDeserializer, Unexpected, Visitor,
+    },
+    forward_to_deserialize_any,
+    ser::{self, Impossible, SerializeSeq, SerializeStruct},
+    Deserializer, Serialize, Serializer,
+};
+use serde_json::{Map, Value};
+use simulink_model::snake_case;
+use std::io;
+
+/// Values of a signal in a [Record]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Values {
+    /// Scalar signal
+    Scalar(f64),
+    /// Array signal
+    Array(Vec<f64>),
+}
+impl Values {
+    /// Returns the values as a slice
+    pub fn as_slice(&self) -> &[f64] {
+        match self {
+            Values::Scalar(value) => std::slice::from_ref(value),
+            Values::Array(values) => values,
+        }
+    }
+    fn from_json(name: &str, value: &Value) -> io::Result<Self> {
+        let number = |value: &Value| match value {
+            Value::Number(number) => number.as_f64(),
+            // NaN and infinite values are written as null
+            Value::Null => Some(f64::NAN),
+            _ => None,
+        };
+        let values = match value {
+            Value::Array(values) => values
+                .iter()
+                .map(number)
+                .collect::<Option<_>>()
+                .map(Values::Array),
+            value => number(value).map(Values::Scalar),
+        };
+        values.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("signal {} is not numeric", name),
+            )
+        })
+    }
+}
+
+/// Collects the selection of signals, given by their Simulink or snake case names
+///
+/// An empty or a repeated name is an error
+pub(super) fn selection(names: &[&str]) -> io::Result<Vec<String>> {
+    if let Some(name) = names
+        .iter()
+        .enumerate()
+        .find_map(|(i, name)| (name.is_empty() || names[..i].contains(name)).then_some(name))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid signal selection {:?}", name),
+        ));
+    }
+    Ok(names.iter().map(|name| name.to_string()).collect())
+}
+
+/// Checks that the selected signals are all in `signals`, e.g. the signals of the CSV header
+pub(super) fn check_selection<'a, I>(
+    selection: &[String],
+    signals: I,
+    source: &str,
+) -> io::Result<()>
+where
+    I: Iterator<Item = &'a str> + Clone,
+{
+    match selection
+        .iter()
+        .find(|selected| !signals.clone().any(|name| matches(selected, name)))
+    {
+        Some(selected) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("signal {} is not in the {}", selected, source),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Checks if a signal is selected, given by its Simulink or snake case name
+///
+/// All the signals are selected if the selection is empty
+pub(super) fn is_selected(selection: &[String], name: &str) -> bool {
+    selection.is_empty() || selection.iter().any(|selected| matches(selected, name))
+}
+
+/// Checks if a signal is given by its Simulink or snake case name
+fn matches(selected: &str, name: &str) -> bool {
+    selected == name || selected == snake_case(name)
+}
+
+/// Values of some signals at one time step, keyed by the Simulink signal names
+///
+/// A record is converted from and to the inputs and outputs structures of the controllers
+/// with their serde implementations
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Record {
+    /// Signals, in order
+    pub signals: Vec<(String, Values)>,
+}
+impl Record {
+    /// Creates an empty record
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Creates a record from the fields of a structure, e.g. the inputs of a controller
+    pub fn from_serialize<T: Serialize>(value: &T) -> io::Result<Self> {
+        let mut record = Self::new();
+        record.serialize(value)?;
+        Ok(record)
+    }
+    /// Adds the fields of a structure, e.g. the outputs of a controller, to the record
+    pub fn serialize<T: Serialize>(&mut self, value: &T) -> io::Result<()> {
+        value
+            .serialize(RecordSerializer(self))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    /// Maps the record onto a structure, e.g. the inputs of a controller
+    ///
+    /// The signals that are not fields of the structure are ignored,
+    /// whereas the fields that are not signals of the record fail the deserialization.
+    /// A structure is mapped from some of its fields with [Partial](crate::Partial),
+    /// the other fields keeping their default values:
+    /// ```ignore
+    /// let Partial(inputs) = record.deserialize::<Partial<ExtU_M1SA_Control_OA_T>>()?;
+    /// ```
+    pub fn deserialize<T: DeserializeOwned>(&self) -> io::Result<T> {
+        T::deserialize(RecordDeserializer(self))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+    /// Inserts a signal, replacing the values of the signal of the same name
+    pub fn insert<S: Into<String>>(&mut self, name: S, values: Values) {
+        let name = name.into();
+        match self.signals.iter_mut().find(|(signal, _)| *signal == name) {
+            Some((_, v)) => *v = values,
+            None => self.signals.push((name, values)),
+        }
+    }
+    /// Returns the values of a signal given its Simulink or snake case name
+    pub fn get(&self, name: &str) -> Option<&Values> {
+        self.signals
+            .iter()
+            .find(|(signal, _)| signal == name)
+            .or_else(|| {
+                self.signals
+                    .iter()
+                    .find(|(signal, _)| snake_case(signal) == name)
+            })
+            .map(|(_, values)| values)
+    }
+    /// Returns the number of signals
+    pub fn len(&self) -> usize {
+        self.signals.len()
+    }
+    /// Checks if the record has no signals
+    pub fn is_empty(&self) -> bool {
+        self.signals.is_empty()
+    }
+    /// Keeps only the selected signals
+    pub(super) fn retain(&mut self, selection: &[String], source: &str) -> io::Result<()> {
+        check_selection(
+            selection,
+            self.signals.iter().map(|(name, _)| name.as_str()),
+            source,
+        )?;
+        self.signals
+            .retain(|(name, _)| is_selected(selection, name));
+        Ok(())
+    }
+    /// Returns the record from a JSON object
+    pub(super) fn from_json(object: Map<String, Value>) -> io::Result<Self> {
+        let signals = object
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), Values::from_json(name, value)?)))
+            .collect::<io::Result<_>>()?;
+        Ok(Self { signals })
+    }
+}
+
+/// Value of a signal element
+struct Number(f64);
+
+macro_rules! integer {
+    ($($deserialize:ident $visit:ident $ty:ty),*) => {
+        $(
+            fn $deserialize<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                let value = self.0 as $ty;
+                if value as f64 != self.0 {
+                    return Err(de::Error::invalid_value(Unexpected::Float(self.0), &visitor));
+                }
+                visitor.$visit(value)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Number {
+    type Error = Error;
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f64(self.0)
+    }
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(self.0 != 0.)
+    }
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_f32(self.0 as f32)
+    }
+    integer!(
+        deserialize_i8 visit_i8 i8,
+        deserialize_u8 visit_u8 u8,
+        deserialize_i16 visit_i16 i16,
+        deserialize_u16 visit_u16 u16,
+        deserialize_i32 visit_i32 i32,
+        deserialize_u32 visit_u32 u32,
+        deserialize_i64 visit_i64 i64,
+        deserialize_u64 visit_u64 u64
+    );
+    forward_to_deserialize_any! {
+        f64 char str string bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any i128 u128
+    }
+}
+impl<'de> IntoDeserializer<'de, Error> for Number {
+    type Deserializer = Self;
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// Deserializer of the values of a signal, either a number or a sequence of numbers
+struct ValuesDeserializer<'a>(&'a Values);
+impl<'de, 'a> IntoDeserializer<'de, Error> for ValuesDeserializer<'a> {
+    type Deserializer = Self;
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+impl<'de, 'a> Deserializer<'de> for ValuesDeserializer<'a> {
+    type Error = Error;
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Values::Scalar(value) => Number(*value).deserialize_any(visitor),
+            Values::Array(values) => {
+                SeqDeserializer::new(values.iter().map(|&value| Number(value)))
+                    .deserialize_any(visitor)
+            }
+        }
+    }
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+/// Deserializer of a [Record] that leaves out the signals that are not fields of the structure
+struct RecordDeserializer<'a>(&'a Record);
+impl<'de, 'a> Deserializer<'de> for RecordDeserializer<'a> {
+    type Error = Error;
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        MapDeserializer::new(
+            self.0
+                .signals
+                .iter()
+                .map(|(name, values)| (name.as_str(), ValuesDeserializer(values))),
+        )
+        .deserialize_any(visitor)
+    }
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        MapDeserializer::new(
+            self.0
+                .signals
+                .iter()
+                .filter(|(name, _)| fields.contains(&name.as_str()))
+                .map(|(name, values)| (name.as_str(), ValuesDeserializer(values))),
+        )
+        .deserialize_any(visitor)
+    }
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+/// Error of the values of a signal that are not numbers
+const NOT_NUMERIC: &str = "not numeric";
+
+/// Implements the methods of a serializer that fail with an error message,
+/// given their argument types
+macro_rules! unsupported {
+    ($error:expr; $($method:ident($($arg:ty),*) -> $ok:ty;)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ok, Error> {
+                Err(ser::Error::custom($error))
+            }
+        )*
+    };
+}
+
+/// Serializer of a signal element
+struct NumberSerializer;
+
+macro_rules! number {
+    ($($method:ident $ty:ty),*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<f64, Error> {
+                Ok(value as f64)
+            }
+        )*
+    };
+}
+
+impl Serializer for NumberSerializer {
+    type Ok = f64;
+    type Error = Error;
+    type SerializeSeq = Impossible<f64, Error>;
+    type SerializeTuple = Impossible<f64, Error>;
+    type SerializeTupleStruct = Impossible<f64, Error>;
+    type SerializeTupleVariant = Impossible<f64, Error>;
+    type SerializeMap = Impossible<f64, Error>;
+    type SerializeStruct = Impossible<f64, Error>;
+    type SerializeStructVariant = Impossible<f64, Error>;
+    fn serialize_bool(self, value: bool) -> Result<f64, Error> {
+        Ok(if value { 1. } else { 0. })
+    }
+    number!(
+        serialize_i8 i8,
+        serialize_i16 i16,
+        serialize_i32 i32,
+        serialize_i64 i64,
+        serialize_u8 u8,
+        serialize_u16 u16,
+        serialize_u32 u32,
+        serialize_u64 u64,
+        serialize_f32 f32,
+        serialize_f64 f64
+    );
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<f64, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<f64, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<f64, Error> {
+        Err(ser::Error::custom(NOT_NUMERIC))
+    }
+    unsupported! {
+        NOT_NUMERIC;
+        serialize_char(char) -> f64;
+        serialize_str(&str) -> f64;
+        serialize_bytes(&[u8]) -> f64;
+        serialize_none() -> f64;
+        serialize_unit() -> f64;
+        serialize_unit_struct(&'static str) -> f64;
+        serialize_unit_variant(&'static str, u32, &'static str) -> f64;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+}
+
+/// Serializer of the values of a signal, either a number or a sequence of numbers
+struct ValuesSerializer;
+
+/// Elements of an array signal
+struct ArraySerializer(Vec<f64>);
+impl SerializeSeq for ArraySerializer {
+    type Ok = Values;
+    type Error = Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.0.push(value.serialize(NumberSerializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Values, Error> {
+        Ok(Values::Array(self.0))
+    }
+}
+impl ser::SerializeTuple for ArraySerializer {
+    type Ok = Values;
+    type Error = Error;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Values, Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+macro_rules! scalar {
+    ($($method:ident $ty:ty),*) => {
+        $(
+            fn $method(self, value: $ty) -> Result<Values, Error> {
+                NumberSerializer.$method(value).map(Values::Scalar)
+            }
+        )*
+    };
+}
+
+impl Serializer for ValuesSerializer {
+    type Ok = Values;
+    type Error = Error;
+    type SerializeSeq = ArraySerializer;
+    type SerializeTuple = ArraySerializer;
+    type SerializeTupleStruct = Impossible<Values, Error>;
+    type SerializeTupleVariant = Impossible<Values, Error>;
+    type SerializeMap = Impossible<Values, Error>;
+    type SerializeStruct = Impossible<Values, Error>;
+    type SerializeStructVariant = Impossible<Values, Error>;
+    scalar!(
+        serialize_bool bool,
+        serialize_i8 i8,
+        serialize_i16 i16,
+        serialize_i32 i32,
+        serialize_i64 i64,
+        serialize_u8 u8,
+        serialize_u16 u16,
+        serialize_u32 u32,
+        serialize_u64 u64,
+        serialize_f32 f32,
+        serialize_f64 f64
+    );
+    fn serialize_seq(self, len: Option<usize>) -> Result<ArraySerializer, Error> {
+        Ok(ArraySerializer(Vec::with_capacity(len.unwrap_or_default())))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<ArraySerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Values, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Values, Error> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Values, Error> {
+        Err(ser::Error::custom(NOT_NUMERIC))
+    }
+    unsupported! {
+        NOT_NUMERIC;
+        serialize_char(char) -> Values;
+        serialize_str(&str) -> Values;
+        serialize_bytes(&[u8]) -> Values;
+        serialize_none() -> Values;
+        serialize_unit() -> Values;
+        serialize_unit_struct(&'static str) -> Values;
+        serialize_unit_variant(&'static str, u32, &'static str) -> Values;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+}
+
+/// Serializer of the fields of a structure into a [Record]
+///
+/// The values are serialized as they are, including the NaN and infinite values
+struct RecordSerializer<'a>(&'a mut Record);
+impl SerializeStruct for RecordSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        let values = value
+            .serialize(ValuesSerializer)
+            .map_err(|_| ser::Error::custom(format!("signal {} is not numeric", name)))?;
+        self.0.insert(name, values);
+        Ok(())
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Serializer of the entries of a map into a [Record], the keys are the signal names
+struct RecordMapSerializer<'a> {
+    record: &'a mut Record,
+    name: Option<String>,
+}
+impl ser::SerializeMap for RecordMapSerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+        match key.serialize(serde_json::value::Serializer) {
+            Ok(Value::String(name)) => {
+                self.name = Some(name);
+                Ok(())
+            }
+            _ => Err(ser::Error::custom("the signal names must be strings")),
+        }
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        let name = self.name.take().unwrap_or_default();
+        let values = value
+            .serialize(ValuesSerializer)
+            .map_err(|_| ser::Error::custom(format!("signal {} is not numeric", name)))?;
+        self.record.insert(name, values);
+        Ok(())
+    }
+    fn end(self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Error of a value that is not a structure
+const NOT_STRUCTURE: &str = "a record is made from a structure";
+
+impl<'a> Serializer for RecordSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = RecordMapSerializer<'a>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<(), Error>;
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        Ok(self)
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<RecordMapSerializer<'a>, Error> {
+        Ok(RecordMapSerializer {
+            record: self.0,
+            name: None,
+        })
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<(), Error> {
+        Err(ser::Error::custom(NOT_STRUCTURE))
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), Error> {
+        Err(ser::Error::custom(NOT_STRUCTURE))
+    }
+    unsupported! {
+        NOT_STRUCTURE;
+        serialize_bool(bool) -> ();
+        serialize_i8(i8) -> ();
+        serialize_i16(i16) -> ();
+        serialize_i32(i32) -> ();
+        serialize_i64(i64) -> ();
+        serialize_u8(u8) -> ();
+        serialize_u16(u16) -> ();
+        serialize_u32(u32) -> ();
+        serialize_u64(u64) -> ();
+        serialize_f32(f32) -> ();
+        serialize_f64(f64) -> ();
+        serialize_char(char) -> ();
+        serialize_str(&str) -> ();
+        serialize_bytes(&[u8]) -> ();
+        serialize_none() -> ();
+        serialize_unit() -> ();
+        serialize_unit_struct(&'static str) -> ();
+        serialize_unit_variant(&'static str, u32, &'static str) -> ();
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
+}
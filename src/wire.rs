@@ -0,0 +1,92 @@
+use std::{error::Error, fmt::Display};
+
+/// Compact binary encoding of a Simulink structure
+///
+/// The properties of a numeric type are encoded in order as little-endian values of their
+/// C data types, without padding nor length prefixes, the other properties are left out.
+/// The layout only depends on the interface of the model, so a frame, the encoded structure
+/// prefixed with the model interface hash, is decoded only by a controller with the same
+/// interface.
+/// Encoding and decoding do not allocate, e.g. to stream the inputs and outputs of
+/// a controller over UDP or shared memory:
+/// ```ignore
+/// let mut frame = [0u8; <ExtY_M1SA_Control_OA_T as Wire>::FRAME_LEN];
+/// controller.outputs.encode_frame(&mut frame)?;
+/// socket.send(&frame)?;
+/// ```
+pub trait Wire {
+    /// Length of the encoded structure in bytes
+    const WIRE_LEN: usize;
+    /// Interface hash of the model, that describes the layout of the encoded structure
+    const INTERFACE_HASH: u64;
+    /// Length of a frame in bytes, the interface hash followed by the encoded structure
+    const FRAME_LEN: usize = 8 + Self::WIRE_LEN;
+    /// Encodes the structure into `data`
+    ///
+    /// Returns an error if the length of `data` is not [Wire::WIRE_LEN]
+    fn encode(&self, data: &mut [u8]) -> Result<(), WireError>;
+    /// Sets the structure from its encoding
+    ///
+    /// Returns an error if the length of `data` is not [Wire::WIRE_LEN]
+    fn decode(&mut self, data: &[u8]) -> Result<(), WireError>;
+    /// Encodes the structure into a frame
+    ///
+    /// Returns an error if the length of `frame` is not [Wire::FRAME_LEN]
+    fn encode_frame(&self, frame: &mut [u8]) -> Result<(), WireError> {
+        if frame.len() != Self::FRAME_LEN {
+            return Err(WireError::Length {
+                expected: Self::FRAME_LEN,
+                found: frame.len(),
+            });
+        }
+        let (hash, data) = frame.split_at_mut(8);
+        hash.copy_from_slice(&Self::INTERFACE_HASH.to_le_bytes());
+        self.encode(data)
+    }
+    /// Sets the structure from a frame
+    ///
+    /// Returns an error if the length of `frame` is not [Wire::FRAME_LEN],
+    /// or if the frame was encoded with another interface
+    fn decode_frame(&mut self, frame: &[u8]) -> Result<(), WireError> {
+        if frame.len() != Self::FRAME_LEN {
+            return Err(WireError::Length {
+                expected: Self::FRAME_LEN,
+                found: frame.len(),
+            });
+        }
+        let (hash, data) = frame.split_at(8);
+        let hash = u64::from_le_bytes(hash.try_into().unwrap());
+        if hash != Self::INTERFACE_HASH {
+            return Err(WireError::Interface {
+                expected: Self::INTERFACE_HASH,
+                found: hash,
+            });
+        }
+        self.decode(data)
+    }
+}
+
+/// Error of the [Wire] encoding
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WireError {
+    /// The length of the buffer does not match the length of the encoding
+    Length { expected: usize, found: usize },
+    /// The frame was encoded with another model interface
+    Interface { expected: u64, found: u64 },
+}
+impl Display for WireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WireError::Length { expected, found } => {
+                write!(f, "expected {} bytes, found {}", expected, found)
+            }
+            WireError::Interface { expected, found } => write!(
+                f,
+                "frame has interface hash {:016x}, expected {:016x}",
+                found, expected
+            ),
+        }
+    }
+}
+impl Error for WireError {}
@@ -0,0 +1,36 @@
+use simulink_rs::Sys;
+use std::env;
+use std::path::Path;
+
+#[test]
+fn allowlist() {
+    let path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("wrapper");
+    let sys = Sys::builder()
+        .folder(path.to_str().unwrap())
+        .allowlist_type("rtwtypes_.*")
+        .build();
+    let allowlist = sys.allowlist();
+    assert_eq!(
+        allowlist.types(),
+        &[
+            "DW_M1SA_Control_OA_T",
+            "ExtU_M1SA_Control_OA_T",
+            "ExtY_M1SA_Control_OA_T",
+            "ConstP_M1SA_Control_OA_T",
+            "RT_MODEL_M1SA_Control_OA_T",
+            "tag_RTM_M1SA_Control_OA_T",
+            "rtwtypes_.*"
+        ]
+    );
+    assert_eq!(
+        allowlist.functions(),
+        &[
+            "M1SA_Control_OA_initialize",
+            "M1SA_Control_OA_step",
+            "M1SA_Control_OA_terminate"
+        ]
+    );
+    assert_eq!(allowlist.variables(), &["M1SA_Control_OA_ConstP"]);
+}
@@ -0,0 +1,23 @@
+use simulink_rs::{OptLevel, Sys};
+
+#[test]
+fn clang_args() {
+    let sys = Sys::builder()
+        .define("MODEL", Some("M1SA_Control_OA"))
+        .define(String::from("NUMST"), Some(1.to_string()))
+        .define("TID01EQ", None::<&str>)
+        .flag("-ffp-contract=off")
+        .opt_level(OptLevel::O3)
+        .warnings_into_errors(true)
+        .build();
+    assert_eq!(
+        sys.compiler().clang_args(),
+        vec![
+            "-DMODEL=M1SA_Control_OA",
+            "-DNUMST=1",
+            "-DTID01EQ",
+            "-ffp-contract=off",
+            "-O3"
+        ]
+    );
+}
@@ -0,0 +1,55 @@
+//! Rust replacement of the bindings and of the C entry points of the `M1SA_Control_OA` model
+
+pub type real_T = f64;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DW_M1SA_Control_OA_T {
+    pub Fxcontroller_DSTATE: [real_T; 3usize],
+    pub Fycontroller_DSTATE: [real_T; 3usize],
+    pub Fzcontroller_DSTATE: [real_T; 3usize],
+    pub Mxcontroller_DSTATE: [real_T; 3usize],
+    pub Mycontroller_DSTATE: [real_T; 3usize],
+    pub Mzcontroller_DSTATE: [real_T; 3usize],
+    pub OA_SA_dyn_states: [real_T; 335usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ExtU_M1SA_Control_OA_T {
+    pub LC_FxyzMxyz_CG: [real_T; 6usize],
+    pub SA_offsetF_cmd: [real_T; 335usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ExtY_M1SA_Control_OA_T {
+    pub Res_Act_F: [real_T; 335usize],
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct tag_RTM_M1SA_Control_OA_T {
+    pub dwork: *mut DW_M1SA_Control_OA_T,
+}
+pub type RT_MODEL_M1SA_Control_OA_T = tag_RTM_M1SA_Control_OA_T;
+
+/// Resets the states
+pub unsafe extern "C" fn M1SA_Control_OA_initialize(
+    M1SA_Control_OA_M: *mut RT_MODEL_M1SA_Control_OA_T,
+) {
+    let dwork = &mut *(*M1SA_Control_OA_M).dwork;
+    dwork.OA_SA_dyn_states = [0.; 335];
+}
+/// Adds the states to the offsets and increments the states
+pub unsafe extern "C" fn M1SA_Control_OA_step(
+    M1SA_Control_OA_M: *mut RT_MODEL_M1SA_Control_OA_T,
+    M1SA_Control_OA_U: *mut ExtU_M1SA_Control_OA_T,
+    M1SA_Control_OA_Y: *mut ExtY_M1SA_Control_OA_T,
+) {
+    let dwork = &mut *(*M1SA_Control_OA_M).dwork;
+    let u = &*M1SA_Control_OA_U;
+    let y = &mut *M1SA_Control_OA_Y;
+    for i in 0..335 {
+        y.Res_Act_F[i] = u.SA_offsetF_cmd[i] + dwork.OA_SA_dyn_states[i];
+        dwork.OA_SA_dyn_states[i] += 1.;
+    }
+    y.Res_Act_F[0] += u.LC_FxyzMxyz_CG.iter().sum::<f64>();
+}
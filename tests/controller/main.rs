@@ -0,0 +1,675 @@
+#![allow(non_camel_case_types, non_snake_case, clippy::missing_safety_doc)]
+
+mod bindings;
+use bindings::*;
+
+simulink_rs::include_model!(
+    "tests/wrapper",
+    name = "M1SA",
+    bindings = false,
+    derive(PartialEq)
+);
+
+#[test]
+fn step() {
+    let mut ctrl = M1SA::new();
+    ctrl.inputs.SA_offsetF_cmd[1] = 2.;
+    ctrl.inputs.LC_FxyzMxyz_CG[0] = 1.;
+    ctrl.step();
+    ctrl.step();
+    assert_eq!(ctrl.outputs.Res_Act_F[0], 2.);
+    assert_eq!(ctrl.outputs.Res_Act_F[1], 3.);
+    assert_ne!(ctrl, M1SA::new());
+}
+
+/// Steps any controller until its first output is positive
+fn steps_to_output<C>(controller: &mut C) -> usize
+where
+    C: simulink_rs::SimulinkController<Outputs = ExtY_M1SA_Control_OA_T>,
+{
+    let mut n = 0;
+    while controller.outputs().Res_Act_F[0] <= 0. {
+        controller.step();
+        n += 1;
+    }
+    n
+}
+
+#[test]
+fn controller_trait() {
+    use simulink_rs::SimulinkController;
+
+    let mut ctrl = <M1SA as SimulinkController>::new();
+    ctrl.inputs_mut().LC_FxyzMxyz_CG[0] = -2.;
+    assert_eq!(steps_to_output(&mut ctrl), 4);
+    assert_eq!(ctrl.states().OA_SA_dyn_states[0], 4.);
+    ctrl.reset();
+    assert_eq!(ctrl, M1SA::new());
+    assert_eq!(M1SA::sample_time(), None);
+    let info = M1SA::info();
+    assert_eq!(info.name, "M1SA_Control_OA");
+    assert_eq!(info.model_version, Some("9.6"));
+}
+
+#[test]
+fn batch() {
+    use simulink_rs::SimulinkController;
+
+    // the states carry over from one step to the next within a single batch call
+    let mut ctrl = M1SA::new();
+    let mut states = vec![];
+    ctrl.run(
+        4,
+        |_, inputs| inputs.LC_FxyzMxyz_CG[0] = -2.,
+        |_, _, x| states.push(x.OA_SA_dyn_states[0]),
+    );
+    assert_eq!(states, [1., 2., 3., 4.]);
+    assert_eq!(ctrl.states().OA_SA_dyn_states[0], 4.);
+
+    let mut steps = vec![];
+    ctrl.run_while(
+        |i, _| i < 2,
+        |i, _, x| steps.push((i, x.OA_SA_dyn_states[0])),
+    );
+    assert_eq!(steps, [(0, 5.), (1, 6.)]);
+}
+
+#[test]
+fn accessors() {
+    let mut ctrl = M1SA::new();
+    ctrl.set_sa_offset_f_cmd(&[1.; 335]).unwrap();
+    assert_eq!(
+        ctrl.set_lc_fxyz_mxyz_cg(&[1.; 5]),
+        Err(simulink_rs::SignalError::Length {
+            signal: "LC_FxyzMxyz_CG",
+            expected: 6,
+            found: 5
+        })
+    );
+    ctrl.set_lc_fxyz_mxyz_cg(&[1.; 6]).unwrap();
+    assert_eq!(ctrl.lc_fxyz_mxyz_cg(), &[1.; 6]);
+    ctrl.step();
+    assert_eq!(ctrl.res_act_f()[..2], [7., 1.]);
+    assert_eq!(
+        ctrl.set_sa_offset_f_cmd(&[]).unwrap_err().to_string(),
+        "signal SA_offsetF_cmd expects 335 values, found 0"
+    );
+}
+
+#[test]
+fn registry() {
+    use simulink_rs::{Direction, SignalError};
+
+    let mut ctrl = M1SA::new();
+    let signals = ctrl.signals();
+    assert_eq!(
+        signals
+            .iter()
+            .map(|signal| (signal.name, signal.direction, signal.size, signal.offset))
+            .collect::<Vec<_>>(),
+        vec![
+            ("LC_FxyzMxyz_CG", Direction::Input, 6, 0),
+            ("SA_offsetF_cmd", Direction::Input, 335, 48),
+            ("Res_Act_F", Direction::Output, 335, 0),
+        ]
+    );
+    assert_eq!(signals[0].dtype, "real_T");
+    assert_eq!(signals[0].block_path, Some("<Root>/LC_FxyzMxyz_CG"));
+    ctrl.set("lc_fxyz_mxyz_cg", &[1.; 6]).unwrap();
+    ctrl.set("SA_offsetF_cmd", &[2.; 335]).unwrap();
+    ctrl.step();
+    assert_eq!(ctrl.get("LC_FxyzMxyz_CG"), Some(&[1.; 6][..]));
+    assert_eq!(ctrl.get("res_act_f").unwrap()[..2], [8., 2.]);
+    assert_eq!(ctrl.get("x"), None);
+    assert_eq!(
+        ctrl.set("Res_Act_F", &[0.; 335]),
+        Err(SignalError::NotFound("Res_Act_F".into()))
+    );
+}
+
+#[test]
+fn flat() {
+    assert_eq!(M1SA::INPUTS_LEN, 341);
+    assert_eq!(M1SA::OUTPUTS_LEN, 335);
+    assert_eq!(M1SA::LC_FXYZ_MXYZ_CG_OFFSET, 0);
+    assert_eq!(M1SA::SA_OFFSET_F_CMD_OFFSET, 6);
+    assert_eq!(M1SA::RES_ACT_F_OFFSET, 0);
+
+    let mut ctrl = M1SA::new();
+    let inputs: Vec<f64> = (0..M1SA::INPUTS_LEN).map(|i| i as f64).collect();
+    ctrl.inputs_from_slice(&inputs).unwrap();
+    assert_eq!(ctrl.inputs.LC_FxyzMxyz_CG, [0., 1., 2., 3., 4., 5.]);
+    assert_eq!(ctrl.inputs.SA_offsetF_cmd[0], 6.);
+    assert!(ctrl.inputs_from_slice(&inputs[1..]).is_err());
+    ctrl.step();
+    let outputs = ctrl.outputs_to_vec();
+    assert_eq!(outputs[..2], [21., 7.]);
+    let mut data = vec![0.; M1SA::OUTPUTS_LEN];
+    ctrl.outputs_into(&mut data).unwrap();
+    assert_eq!(data, outputs);
+    assert!(ctrl.outputs_into(&mut data[1..]).is_err());
+}
+
+#[test]
+fn simulation() {
+    use simulink_rs::simulation::Simulation;
+
+    let mut sim = Simulation::new(M1SA::new()).record_states(true);
+    let data = vec![1f64; 3 * M1SA::INPUTS_LEN];
+    let record = sim.run(data.chunks(M1SA::INPUTS_LEN)).unwrap();
+    assert_eq!(record.outputs.time, [0., 1., 2.]);
+    assert_eq!(record.outputs.width, M1SA::OUTPUTS_LEN);
+    assert_eq!(record.outputs.row(2)[..2], [9., 3.]);
+    let second = record.outputs.row(1).to_vec();
+    let states = record.states.unwrap();
+    assert_eq!(states.width, 6 * 3 + 335);
+    assert_eq!(states.row(0)[6 * 3], 1.);
+
+    let mut ctrl = M1SA::new();
+    ctrl.inputs.LC_FxyzMxyz_CG = [1.; 6];
+    let inputs = ctrl.inputs;
+    let record = Simulation::new(M1SA::new())
+        .run([0.5, 1.5].map(|t| (t, inputs)))
+        .unwrap();
+    assert_eq!(record.outputs.time, [0.5, 1.5]);
+    assert_eq!(record.states, None);
+    for (_, outputs) in record.outputs.iter() {
+        ctrl.step();
+        assert_eq!(outputs, ctrl.outputs_to_vec());
+    }
+
+    assert!(Simulation::new(M1SA::new()).run([vec![0.; 3]]).is_err());
+
+    // the controller is stepped as the samples are pulled, up to the invalid sample
+    let mut sim = Simulation::new(M1SA::new());
+    let samples = (0..).map(|i| vec![1.; if i < 2 { M1SA::INPUTS_LEN } else { 3 }]);
+    assert!(sim.run(samples).is_err());
+    assert_eq!(sim.controller().outputs_to_vec(), second);
+
+    let mut series = simulink_rs::simulation::TimeSeries::with_capacity(3, 1);
+    assert!(series.push(0., &ctrl.outputs).is_err());
+    assert!(series.is_empty() && series.data.is_empty());
+}
+
+#[test]
+fn snapshot() {
+    use simulink_rs::{SimulinkController, Snapshot, SnapshotError};
+    let mut ctrl = M1SA::new();
+    ctrl.inputs.SA_offsetF_cmd[0] = 1.;
+    ctrl.step();
+    ctrl.step();
+    let snapshot = ctrl.snapshot();
+    assert_eq!(snapshot.model, "M1SA_Control_OA");
+    assert_eq!(snapshot.model_version.as_deref(), Some("9.6"));
+    assert_eq!(snapshot.interface_hash, M1SA::info().interface_hash);
+    assert!(snapshot.inputs.is_none());
+    let path =
+        std::env::temp_dir().join(format!("simulink-rs-snapshot-{}.json", std::process::id()));
+    ctrl.snapshot_with_io().write(&path).unwrap();
+    let snapshot_with_io: Snapshot<M1SA> = Snapshot::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut resumed = M1SA::new();
+    resumed.restore(&snapshot).unwrap();
+    assert_eq!(resumed.states(), ctrl.states());
+    assert_ne!(resumed, ctrl);
+    resumed.restore(&snapshot_with_io).unwrap();
+    assert_eq!(resumed, ctrl);
+    ctrl.step();
+    resumed.step();
+    assert_eq!(resumed, ctrl);
+
+    let mut other = snapshot.clone();
+    other.model = "Gain_model".into();
+    assert!(matches!(
+        resumed.restore(&other),
+        Err(SnapshotError::Model { .. })
+    ));
+    let mut other = snapshot.clone();
+    other.interface_hash ^= 1;
+    assert!(matches!(
+        resumed.restore(&other),
+        Err(SnapshotError::Interface { .. })
+    ));
+    let mut other = snapshot;
+    other.format += 1;
+    assert!(matches!(
+        resumed.restore(&other),
+        Err(SnapshotError::Format { .. })
+    ));
+    assert_eq!(resumed, ctrl);
+}
+
+#[test]
+fn snapshot_non_finite() {
+    use simulink_rs::{SimulinkController, Snapshot};
+    let mut ctrl = M1SA::new();
+    ctrl.step();
+    ctrl.states_mut().OA_SA_dyn_states[1] = f64::NAN;
+    ctrl.states_mut().OA_SA_dyn_states[2] = f64::INFINITY;
+    ctrl.inputs.SA_offsetF_cmd[3] = f64::NEG_INFINITY;
+    let path = std::env::temp_dir().join(format!(
+        "simulink-rs-snapshot-nan-{}.json",
+        std::process::id()
+    ));
+    ctrl.snapshot_with_io().write(&path).unwrap();
+    let snapshot: Snapshot<M1SA> = Snapshot::read(&path).unwrap();
+
+    let states = &snapshot.states.OA_SA_dyn_states;
+    assert!(states[1].is_nan());
+    assert_eq!(states[2], f64::INFINITY);
+    assert_eq!(states[3..], ctrl.states().OA_SA_dyn_states[3..]);
+    let inputs = snapshot.inputs.unwrap();
+    assert_eq!(inputs.SA_offsetF_cmd[3], f64::NEG_INFINITY);
+    assert_eq!(inputs.SA_offsetF_cmd[4..], ctrl.inputs.SA_offsetF_cmd[4..]);
+    assert_eq!(snapshot.outputs.unwrap(), ctrl.outputs);
+
+    // a null value that is not listed as non-finite is rejected
+    let json = std::fs::read_to_string(&path).unwrap();
+    let json = json.replace(
+        r#""non_finite":{"states":[[19,"NaN"],[20,"inf"]]"#,
+        r#""non_finite":{"states":[[19,"NaN"]]"#,
+    );
+    std::fs::write(&path, json).unwrap();
+    assert!(Snapshot::<M1SA>::read(&path).is_err());
+    std::fs::remove_file(&path).unwrap();
+}
+
+simulink_rs::golden_test!(
+    golden,
+    M1SA,
+    "tests/controller/golden/inputs.json",
+    "tests/controller/golden/outputs.json",
+);
+
+#[test]
+fn golden_divergence() {
+    use simulink_rs::{
+        testing::{Golden, GoldenError, Tolerance},
+        trace::Trace,
+        SignalError,
+    };
+
+    let inputs = Trace::read("tests/controller/golden/inputs.json").unwrap();
+    let mut outputs = Trace::read("tests/controller/golden/outputs.json").unwrap();
+    outputs.signals[0].data[335 + 7] += 1e-6;
+    let golden = Golden::new(inputs, outputs).unwrap();
+    let Err(GoldenError::Divergence(divergence)) = golden.check(&mut M1SA::new()) else {
+        panic!("expected a divergence")
+    };
+    assert_eq!(divergence.step, 1);
+    assert_eq!(divergence.time, 0.001);
+    assert_eq!(divergence.signal, "res_act_f");
+    assert_eq!(divergence.element, 7);
+    assert_eq!(divergence.found, 1.);
+    assert!(divergence
+        .to_string()
+        .starts_with("res_act_f[7] diverges at step 1"));
+
+    let golden = golden.abs(1e-9);
+    assert!(golden.check(&mut M1SA::new()).is_err());
+    let golden = golden.signal_tolerance("res_act_f", Tolerance::new(0., 1e-5));
+    assert_eq!(golden.check(&mut M1SA::new()), Ok(()));
+
+    let golden = Golden::new(
+        Trace::new(vec![0.]).signal("LC_FxyzMxyz_CG", 3, vec![0.; 3]),
+        Trace::new(vec![0.]),
+    )
+    .unwrap();
+    assert!(matches!(
+        golden.check(&mut M1SA::new()),
+        Err(GoldenError::Signal(_))
+    ));
+    let golden = Golden::new(
+        Trace::new(vec![0.]),
+        Trace::new(vec![0.]).signal("unknown", 1, vec![0.]),
+    )
+    .unwrap();
+    assert_eq!(
+        golden.check(&mut M1SA::new()),
+        Err(GoldenError::Signal(SignalError::NotFound("unknown".into())))
+    );
+    assert!(matches!(
+        Golden::new(Trace::new(vec![0.]), Trace::new(vec![])),
+        Err(GoldenError::Steps { .. })
+    ));
+    // short and ragged recordings are rejected before replaying them
+    assert_eq!(
+        Golden::new(
+            Trace::new(vec![0., 1.]),
+            Trace::new(vec![0., 1.]).signal("res_act_f", 335, vec![0.; 335 + 7]),
+        ),
+        Err(GoldenError::Length {
+            signal: "res_act_f".into(),
+            expected: 2 * 335,
+            found: 335 + 7,
+        })
+    );
+}
+
+#[cfg(feature = "mat")]
+#[test]
+fn mat() {
+    use simulink_rs::mat::MatFile;
+
+    let mat = MatFile::read("tests/mat/matlab.mat").unwrap();
+    let mut ctrl = M1SA::new();
+    ctrl.inputs = mat.deserialize("inputs").unwrap();
+    assert_eq!(ctrl.inputs.LC_FxyzMxyz_CG, [1., 2., 3., 4., 5., 6.]);
+    assert_eq!(ctrl.inputs.SA_offsetF_cmd[..3], [0., 1., 2.]);
+    ctrl.step();
+
+    let mut mat = MatFile::default();
+    mat.serialize("outputs", &ctrl.outputs).unwrap();
+    assert_eq!(
+        mat.get("outputs").unwrap().field("Res_Act_F").unwrap().dims,
+        [335, 1]
+    );
+    let mut bytes = vec![];
+    mat.to_writer(&mut bytes).unwrap();
+    let mat = MatFile::from_reader(bytes.as_slice()).unwrap();
+    let outputs: ExtY_M1SA_Control_OA_T = mat.deserialize("outputs").unwrap();
+    assert_eq!(outputs, ctrl.outputs);
+    assert!(mat
+        .deserialize::<ExtU_M1SA_Control_OA_T>("outputs")
+        .is_err());
+}
+
+/// Returns the header dictionary and the data of a `.npy` file
+fn npy(bytes: &[u8]) -> (String, Vec<f64>) {
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    assert_eq!((10 + len) % 64, 0);
+    let header = String::from_utf8(bytes[10..10 + len].to_vec()).unwrap();
+    let data = bytes[10 + len..]
+        .chunks_exact(8)
+        .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+    (header.trim_end().to_string(), data)
+}
+
+#[test]
+fn npy_recorder() {
+    use simulink_rs::npy::Recorder;
+
+    let dir = std::env::temp_dir().join(format!("simulink-rs-npy-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let mut ctrl = M1SA::new();
+    let mut recorder = Recorder::npy(&ctrl, &dir, &["lc_fxyz_mxyz_cg", "Res_Act_F"]).unwrap();
+    assert_eq!(
+        recorder.signals().collect::<Vec<_>>(),
+        ["LC_FxyzMxyz_CG", "Res_Act_F"]
+    );
+    for i in 0..3 {
+        ctrl.inputs.LC_FxyzMxyz_CG[0] = i as f64;
+        ctrl.step();
+        recorder.record(&ctrl).unwrap();
+    }
+    assert_eq!(recorder.len(), 3);
+    recorder.finish().unwrap();
+
+    let (header, data) = npy(&std::fs::read(dir.join("LC_FxyzMxyz_CG.npy")).unwrap());
+    assert_eq!(
+        header,
+        "{'descr': '<f8', 'fortran_order': False, 'shape': (3, 6), }"
+    );
+    assert_eq!(data.len(), 18);
+    assert_eq!([data[0], data[6], data[12]], [0., 1., 2.]);
+    let (header, data) = npy(&std::fs::read(dir.join("Res_Act_F.npy")).unwrap());
+    assert!(header.ends_with("'shape': (3, 335), }"));
+    assert_eq!(data[2 * 335..], ctrl.outputs.Res_Act_F);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(Recorder::npy(&ctrl, std::env::temp_dir(), &["unknown"]).is_err());
+}
+
+#[test]
+fn npz_recorder() {
+    use simulink_rs::npy::Recorder;
+
+    let path = std::env::temp_dir().join(format!("simulink-rs-{}.npz", std::process::id()));
+    let mut ctrl = M1SA::new();
+    {
+        // the archive is written when the recorder is dropped
+        let mut recorder = Recorder::npz(&ctrl, &path, &[]).unwrap();
+        for _ in 0..2 {
+            ctrl.step();
+            recorder.record(&ctrl).unwrap();
+        }
+    }
+    // the .npy files are streamed into a directory next to the archive, removed at the end
+    let mut spool = path.clone().into_os_string();
+    spool.push(".parts");
+    assert!(!std::path::Path::new(&spool).exists());
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    // end of central directory record
+    let end = &bytes[bytes.len() - 22..];
+    assert_eq!(end[..4], 0x06054b50u32.to_le_bytes());
+    assert_eq!(u16::from_le_bytes([end[10], end[11]]), 3);
+    // first entry
+    assert_eq!(bytes[..4], 0x04034b50u32.to_le_bytes());
+    let size = u32::from_le_bytes(bytes[18..22].try_into().unwrap()) as usize;
+    let name_len = u16::from_le_bytes([bytes[26], bytes[27]]) as usize;
+    assert_eq!(&bytes[30..30 + name_len], b"LC_FxyzMxyz_CG.npy");
+    let start = 30 + name_len;
+    let (header, data) = npy(&bytes[start..start + size]);
+    assert!(header.ends_with("'shape': (2, 6), }"));
+    assert_eq!(data.len(), 12);
+}
+
+#[test]
+fn csv_trace() {
+    use simulink_rs::trace::{CsvReader, CsvWriter, Record, Values};
+
+    let mut ctrl = M1SA::new();
+    let mut writer = CsvWriter::new(vec![]);
+    for i in 0..3 {
+        ctrl.inputs.LC_FxyzMxyz_CG[5] = i as f64;
+        ctrl.inputs.SA_offsetF_cmd[12] = -0.5 * i as f64;
+        let mut record = Record::new();
+        record.insert("time", Values::Scalar(i as f64 * 1e-3));
+        record.serialize(&ctrl.inputs).unwrap();
+        writer.write(&record).unwrap();
+    }
+    let csv = String::from_utf8(writer.into_inner()).unwrap();
+    let header = csv.lines().next().unwrap();
+    assert!(header.starts_with("time,LC_FxyzMxyz_CG[0],"));
+    assert_eq!(header.split(',').count(), 1 + 6 + 335);
+    assert!(header.contains(",SA_offsetF_cmd[12],"));
+
+    let inputs: Vec<ExtU_M1SA_Control_OA_T> = CsvReader::new(csv.as_bytes())
+        .unwrap()
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(inputs.len(), 3);
+    assert_eq!(inputs[2], ctrl.inputs);
+    assert_eq!(inputs[1].SA_offsetF_cmd[12], -0.5);
+
+    let reader = CsvReader::new(csv.as_bytes())
+        .unwrap()
+        .select(&["time", "lc_fxyz_mxyz_cg"])
+        .unwrap();
+    assert_eq!(
+        reader.signals().collect::<Vec<_>>(),
+        ["time", "LC_FxyzMxyz_CG"]
+    );
+    let records: Vec<Record> = reader.collect::<Result<_, _>>().unwrap();
+    assert_eq!(records[1].get("time"), Some(&Values::Scalar(1e-3)));
+    assert_eq!(records[2].get("LC_FxyzMxyz_CG").unwrap().as_slice()[5], 2.);
+    assert!(records[0].deserialize::<ExtU_M1SA_Control_OA_T>().is_err());
+    let simulink_rs::Partial(inputs) = records[2]
+        .deserialize::<simulink_rs::Partial<ExtU_M1SA_Control_OA_T>>()
+        .unwrap();
+    assert_eq!(inputs.LC_FxyzMxyz_CG, ctrl.inputs.LC_FxyzMxyz_CG);
+    assert_eq!(inputs.SA_offsetF_cmd, [0.; 335]);
+
+    // the non-finite values are kept
+    let mut outputs = ExtY_M1SA_Control_OA_T::default();
+    outputs.Res_Act_F[..3].copy_from_slice(&[f64::INFINITY, f64::NEG_INFINITY, f64::NAN]);
+    let record = Record::from_serialize(&outputs).unwrap();
+    let values = record.get("Res_Act_F").unwrap().as_slice();
+    assert_eq!(values[..2], [f64::INFINITY, f64::NEG_INFINITY]);
+    assert!(values[2].is_nan());
+    assert!(Record::from_serialize(&1.).is_err());
+    assert!(CsvReader::new(csv.as_bytes())
+        .unwrap()
+        .select(&["unknown"])
+        .is_err());
+
+    assert!(CsvReader::new(csv.as_bytes())
+        .unwrap()
+        .select(&["time", "time"])
+        .is_err());
+
+    let mut writer = CsvWriter::new(vec![]).select(&["Res_Act_F"]).unwrap();
+    ctrl.step();
+    writer.serialize(&ctrl.outputs).unwrap();
+    assert!(writer.serialize(&ctrl.inputs).is_err());
+    let mut writer = CsvWriter::new(vec![]).select(&["unknown"]).unwrap();
+    assert!(writer.serialize(&ctrl.outputs).is_err());
+    assert!(CsvWriter::new(vec![]).select(&[""]).is_err());
+    assert!(CsvReader::new("x[0],x[2]\n1,2\n".as_bytes()).is_err());
+
+    // the fields are not quoted
+    assert!(CsvReader::new("time,\"x,y\"\n0,1\n".as_bytes()).is_err());
+    let mut reader = CsvReader::new("time,x\n0,\"1\"\n".as_bytes()).unwrap();
+    assert!(reader.next().unwrap().is_err());
+    let mut record = Record::new();
+    record.insert("x,y", Values::Scalar(1.));
+    assert!(CsvWriter::new(vec![]).write(&record).is_err());
+}
+
+#[test]
+fn json_lines_trace() {
+    use simulink_rs::trace::{JsonLinesReader, JsonLinesWriter, Values};
+
+    let mut ctrl = M1SA::new();
+    let mut writer = JsonLinesWriter::new(vec![]).select(&["res_act_f"]).unwrap();
+    let mut outputs = vec![];
+    for _ in 0..3 {
+        ctrl.inputs.LC_FxyzMxyz_CG[0] = f64::NAN;
+        ctrl.step();
+        writer.serialize(&ctrl.outputs).unwrap();
+        outputs.push(ctrl.outputs);
+    }
+    let jsonl = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(jsonl.lines().count(), 3);
+    assert!(jsonl.starts_with("{\"Res_Act_F\":[null,0.0,"));
+
+    let records: Vec<_> = JsonLinesReader::new(jsonl.as_bytes())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let Some(Values::Array(res_act_f)) = records[2].get("Res_Act_F") else {
+        panic!("Res_Act_F is not an array")
+    };
+    assert!(res_act_f[0].is_nan());
+    assert_eq!(res_act_f[1..], outputs[2].Res_Act_F[1..]);
+
+    let reader = JsonLinesReader::new("{\"Res_Act_F\":[1.5],\"time\":0.5}\n\n".as_bytes())
+        .select(&["time"])
+        .unwrap();
+    let records: Vec<_> = reader.collect::<Result<_, _>>().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        records[0].signals,
+        [("time".to_string(), Values::Scalar(0.5))]
+    );
+    let mut reader = JsonLinesReader::new("{\"time\":0.5}\n".as_bytes())
+        .select(&["unknown"])
+        .unwrap();
+    assert!(reader.next().unwrap().is_err());
+    let mut writer = JsonLinesWriter::new(vec![]).select(&["unknown"]).unwrap();
+    assert!(writer.serialize(&ctrl.outputs).is_err());
+    assert!(JsonLinesReader::new("".as_bytes())
+        .select(&["time", "time"])
+        .is_err());
+
+    let inputs = format!("{}\n", serde_json::to_string(&ctrl.inputs).unwrap());
+    let mut reader =
+        JsonLinesReader::new(inputs.as_bytes()).deserialize::<ExtU_M1SA_Control_OA_T>();
+    let u = reader.next().unwrap().unwrap();
+    assert!(u.LC_FxyzMxyz_CG[0].is_nan());
+    assert_eq!(u.SA_offsetF_cmd, ctrl.inputs.SA_offsetF_cmd);
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn wire() {
+    use simulink_rs::{SimulinkController, Wire, WireError};
+
+    assert_eq!(ExtU_M1SA_Control_OA_T::WIRE_LEN, (6 + 335) * 8);
+    assert_eq!(ExtY_M1SA_Control_OA_T::FRAME_LEN, 8 + 335 * 8);
+    assert_eq!(
+        ExtU_M1SA_Control_OA_T::INTERFACE_HASH,
+        M1SA::info().interface_hash
+    );
+
+    let mut ctrl = M1SA::new();
+    ctrl.inputs.LC_FxyzMxyz_CG[1] = 1.5;
+    ctrl.inputs.SA_offsetF_cmd[334] = -2.;
+    ctrl.step();
+    let mut data = [0u8; (6 + 335) * 8];
+    ctrl.inputs.encode(&mut data).unwrap();
+    assert_eq!(data[8..16], 1.5f64.to_le_bytes());
+    assert_eq!(data[data.len() - 8..], (-2f64).to_le_bytes());
+
+    let mut frame = [0u8; ExtY_M1SA_Control_OA_T::FRAME_LEN];
+    ctrl.outputs.encode_frame(&mut frame).unwrap();
+    let mut other = M1SA::new();
+    other.inputs.decode(&data).unwrap();
+    other.outputs_mut().decode_frame(&frame).unwrap();
+    assert_eq!(other.inputs, ctrl.inputs);
+    assert_eq!(other.outputs, ctrl.outputs);
+
+    let mut states = vec![0u8; <M1SA as SimulinkController>::States::FRAME_LEN];
+    ctrl.states().encode_frame(&mut states).unwrap();
+    other.states_mut().decode_frame(&states).unwrap();
+    assert_eq!(other.states(), ctrl.states());
+
+    assert_eq!(
+        other.inputs.decode(&data[1..]),
+        Err(WireError::Length {
+            expected: data.len(),
+            found: data.len() - 1
+        })
+    );
+    frame[0] ^= 1;
+    assert!(matches!(
+        other.outputs.decode_frame(&frame),
+        Err(WireError::Interface { .. })
+    ));
+}
+
+#[test]
+fn patch() {
+    use simulink_rs::{Partial, Patch, SimulinkController};
+
+    let mut ctrl = M1SA::new();
+    ctrl.inputs.SA_offsetF_cmd[3] = 4.;
+    ctrl.inputs
+        .apply_patch(&mut serde_json::Deserializer::from_str(
+            r#"{"LC_FxyzMxyz_CG": [1, 2, 3, 4, 5, 6]}"#,
+        ))
+        .unwrap();
+    assert_eq!(ctrl.inputs.LC_FxyzMxyz_CG, [1., 2., 3., 4., 5., 6.]);
+    assert_eq!(ctrl.inputs.SA_offsetF_cmd[3], 4.);
+
+    let inputs = ctrl.inputs;
+    let error = ctrl
+        .inputs_mut()
+        .apply_patch(
+            serde_json::json!({"LC_FxyzMxyz_CG": vec![0.; 6], "SA_offsetF_cmd": [1.0, 2.0]}),
+        )
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid length 2, expected an array of 335 elements"
+    );
+    assert_eq!(ctrl.inputs, inputs);
+
+    let Partial(outputs) = serde_json::from_str::<Partial<ExtY_M1SA_Control_OA_T>>("{}").unwrap();
+    assert_eq!(outputs, ExtY_M1SA_Control_OA_T::default());
+    assert!(serde_json::from_str::<ExtY_M1SA_Control_OA_T>("{}").is_err());
+}
@@ -0,0 +1,48 @@
+/*
+ * File: Ctypes_model.h
+ *
+ * Code generated for Simulink model 'Ctypes_model'.
+ *
+ * Model version                  : 1.0
+ * Simulink Coder version         : 9.8 (R2022b) 13-May-2022
+ * C/C++ source code generated on : Mon May 15 10:12:31 2023
+ *
+ * Target selection: ert.tlc
+ */
+
+#ifndef RTW_HEADER_Ctypes_model_h_
+#define RTW_HEADER_Ctypes_model_h_
+#include "rtwtypes.h"
+
+/* Block signals and states (default storage) for system '<Root>' */
+typedef struct {
+  double Counter_DSTATE;               /* '<Root>/Counter' */
+} DW_Ctypes_model_T;
+
+/* External inputs (root inport signals with default storage) */
+typedef struct {
+  double u[2];                         /* '<Root>/u' */
+  float Gain;                          /* '<Root>/Gain' */
+  int Count[3];                        /* '<Root>/Count' */
+  char_T Mode;                         /* '<Root>/Mode' */
+  double Type;                         /* '<Root>/Type' */
+} ExtU_Ctypes_model_T;
+
+/* External outputs (root outports fed by signals with default storage) */
+typedef struct {
+  double y[2];                         /* '<Root>/y' */
+  int Total;                           /* '<Root>/Total' */
+  float Self;                          /* '<Root>/Self' */
+} ExtY_Ctypes_model_T;
+
+/* Real-time Model Data Structure */
+struct tag_RTM_Ctypes_model_T {
+  DW_Ctypes_model_T *dwork;
+};
+
+/* Model entry point functions */
+extern void Ctypes_model_initialize(RT_MODEL_Ctypes_model_T *const Ctypes_model_M);
+extern void Ctypes_model_step(RT_MODEL_Ctypes_model_T *const Ctypes_model_M,
+  ExtU_Ctypes_model_T *Ctypes_model_U, ExtY_Ctypes_model_T *Ctypes_model_Y);
+
+#endif                                 /* RTW_HEADER_Ctypes_model_h_ */
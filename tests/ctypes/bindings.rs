@@ -0,0 +1,52 @@
+//! Rust replacement of the bindings and of the C entry points of the `Ctypes_model` model
+
+pub type char_T = ::std::os::raw::c_char;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DW_Ctypes_model_T {
+    pub Counter_DSTATE: f64,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ExtU_Ctypes_model_T {
+    pub u: [f64; 2usize],
+    pub Gain: f32,
+    pub Count: [::std::os::raw::c_int; 3usize],
+    pub Mode: char_T,
+    pub Type: f64,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ExtY_Ctypes_model_T {
+    pub y: [f64; 2usize],
+    pub Total: ::std::os::raw::c_int,
+    pub Self_: f32,
+}
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct tag_RTM_Ctypes_model_T {
+    pub dwork: *mut DW_Ctypes_model_T,
+}
+pub type RT_MODEL_Ctypes_model_T = tag_RTM_Ctypes_model_T;
+
+/// Resets the counter
+pub unsafe extern "C" fn Ctypes_model_initialize(Ctypes_model_M: *mut RT_MODEL_Ctypes_model_T) {
+    (*(*Ctypes_model_M).dwork).Counter_DSTATE = 0.;
+}
+/// Scales the inputs by the gain and sums the counts
+pub unsafe extern "C" fn Ctypes_model_step(
+    Ctypes_model_M: *mut RT_MODEL_Ctypes_model_T,
+    Ctypes_model_U: *mut ExtU_Ctypes_model_T,
+    Ctypes_model_Y: *mut ExtY_Ctypes_model_T,
+) {
+    let dwork = &mut *(*Ctypes_model_M).dwork;
+    let u = &*Ctypes_model_U;
+    let y = &mut *Ctypes_model_Y;
+    for i in 0..2 {
+        y.y[i] = u.u[i] * u.Gain as f64 + u.Type;
+    }
+    y.Total = u.Count.iter().sum::<i32>() + u.Mode as i32;
+    y.Self_ = dwork.Counter_DSTATE as f32;
+    dwork.Counter_DSTATE += 1.;
+}
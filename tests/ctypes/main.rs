@@ -0,0 +1,59 @@
+#![allow(non_camel_case_types, non_snake_case, clippy::missing_safety_doc)]
+
+mod bindings;
+use bindings::*;
+use simulink_rs::{Flatten, Wire};
+
+simulink_rs::include_model!("tests/ctypes", bindings = false);
+
+#[test]
+fn plain_c_types() {
+    let mut ctrl = Ctypes_model::new();
+    ctrl.set_u(&[1., 2.]).unwrap();
+    ctrl.set_gain(&[2.]).unwrap();
+    ctrl.set_count(&[1, 2, 3]).unwrap();
+    ctrl.set_mode(&[4]).unwrap();
+    ctrl.set_type(&[0.5]).unwrap();
+    ctrl.step();
+    ctrl.step();
+    assert_eq!(ctrl.y(), &[2.5, 4.5]);
+    assert_eq!(ctrl.total(), &[10]);
+    assert_eq!(ctrl.self_(), &[1.]);
+    assert_eq!(ctrl.get("Type"), Some(&[0.5][..]));
+    assert!(ctrl.set("Gain", &[1.]).is_err());
+
+    assert_eq!(ctrl.outputs_to_vec(), vec![2.5, 4.5, 10., 1.]);
+    let mut inputs = [0f64; 8];
+    ctrl.inputs.copy_to_slice(&mut inputs).unwrap();
+    assert_eq!(inputs, [1., 2., 2., 1., 2., 3., 4., 0.5]);
+
+    // 2 doubles, a float, 3 ints, a char and a double
+    assert_eq!(<ExtU_Ctypes_model_T as Wire>::WIRE_LEN, 16 + 4 + 12 + 1 + 8);
+    let mut data = [0u8; 41];
+    ctrl.inputs.encode(&mut data).unwrap();
+    let mut inputs = ExtU_Ctypes_model_T::default();
+    inputs.decode(&data).unwrap();
+    assert_eq!(inputs, ctrl.inputs);
+    assert_eq!(ctrl.signals().len(), 8);
+}
+
+#[test]
+fn golden_dtype() {
+    use simulink_rs::{
+        testing::{Golden, GoldenError},
+        trace::Trace,
+    };
+
+    let golden = Golden::new(
+        Trace::new(vec![0.]),
+        Trace::new(vec![0.]).signal("total", 1, vec![0.]),
+    )
+    .unwrap();
+    assert_eq!(
+        golden.check(&mut Ctypes_model::new()),
+        Err(GoldenError::Dtype {
+            signal: "total".into(),
+            dtype: "int",
+        })
+    );
+}
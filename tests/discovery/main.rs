@@ -0,0 +1,107 @@
+use simulink_rs::Sys;
+use std::env;
+use std::path::{Path, PathBuf};
+
+fn sys_folder() -> PathBuf {
+    Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("discovery")
+        .join("sys")
+}
+
+fn file_names(files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .map(|file| {
+            file.strip_prefix(sys_folder())
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn top_level() {
+    let sys = Sys::builder()
+        .folder(sys_folder().to_str().unwrap())
+        .build();
+    assert_eq!(file_names(sys.sources()), vec!["model.c"]);
+    assert_eq!(file_names(sys.headers()), vec!["model.h"]);
+}
+
+#[test]
+fn recursive() {
+    let sys = Sys::builder()
+        .folder(sys_folder().to_str().unwrap())
+        .recursive(true)
+        .exclude("**/test_*.c")
+        .build();
+    assert_eq!(file_names(sys.sources()), vec!["model.c", "sub/util.c"]);
+    assert_eq!(file_names(sys.headers()), vec!["model.h", "sub/util.h"]);
+    assert!(sys.include_dirs().contains(&sys_folder().join("sub")));
+}
+
+#[test]
+fn globs_and_example_mains() {
+    let sys = Sys::builder()
+        .folder(sys_folder().to_str().unwrap())
+        .recursive(true)
+        .glob("*.c")
+        .keep_example_mains()
+        .build();
+    assert_eq!(
+        file_names(sys.sources()),
+        vec![
+            "ert_main.c",
+            "examples/harness.c",
+            "model.c",
+            "sub/test_util.c",
+            "sub/util.c"
+        ]
+    );
+    assert!(sys.headers().is_empty());
+}
+
+#[test]
+fn explicit_files() {
+    let sys = Sys::builder()
+        .folder(sys_folder().to_str().unwrap())
+        .glob("model.h")
+        .source(sys_folder().join("sub").join("util.c"))
+        .include_dir(sys_folder().join("sub"))
+        .build();
+    assert_eq!(file_names(sys.sources()), vec!["sub/util.c"]);
+    assert_eq!(file_names(sys.headers()), vec!["model.h"]);
+    assert_eq!(
+        sys.include_dirs(),
+        &[sys_folder().join("sub"), sys_folder()]
+    );
+}
+
+#[test]
+fn resolved_files() {
+    let sys = Sys::builder()
+        .folder(sys_folder().to_str().unwrap())
+        .recursive(true)
+        .exclude("**/test_*.c")
+        .build();
+    // as passed on by `Sys::compile` to the compilation of the crate
+    env::set_var("SIMULINK_RS_SYS_FOLDER", sys_folder());
+    env::set_var(
+        "SIMULINK_RS_HEADERS",
+        env::join_paths(sys.headers()).unwrap(),
+    );
+    env::set_var(
+        "SIMULINK_RS_SOURCES",
+        env::join_paths(sys.sources()).unwrap(),
+    );
+    let (headers, sources) = simulink_model::resolved_files(&sys_folder()).unwrap();
+    assert_eq!(headers, sys.headers());
+    assert_eq!(sources, sys.sources());
+    assert_eq!(
+        simulink_model::resolved_files(&sys_folder().join("sub")),
+        None
+    );
+}
@@ -0,0 +1,5 @@
+#include "model.h"
+int_T main(int_T argc, const char *argv[])
+{
+  return 0;
+}
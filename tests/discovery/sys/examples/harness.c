@@ -0,0 +1,6 @@
+#include "model.h"
+
+int main(void)
+{
+  return 0;
+}
@@ -0,0 +1 @@
+#include "model.h"
@@ -0,0 +1,3 @@
+/*
+ * File: model.h
+ */
@@ -0,0 +1,90 @@
+#![cfg(feature = "bindgen")]
+
+use simulink_rs::Sys;
+use std::{
+    env, fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+fn digest_line(dir: &Path) -> String {
+    fs::read_to_string(dir.join("controller.rs"))
+        .unwrap()
+        .lines()
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+// the environment variables are global to the process so all the cases run in a single test
+#[test]
+fn pregenerated() {
+    let tmp = env::temp_dir().join(format!("simulink-rs-generated-{}", std::process::id()));
+    let sys_dir = tmp.join("sys");
+    let generated_dir = tmp.join("generated");
+    let out_dir = tmp.join("out");
+    fs::create_dir_all(&sys_dir).unwrap();
+    fs::create_dir_all(&out_dir).unwrap();
+    fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/ctypes/Ctypes_model.h"),
+        sys_dir.join("Ctypes_model.h"),
+    )
+    .unwrap();
+    fs::write(
+        sys_dir.join("Ctypes_model_types.h"),
+        "#ifndef Ctypes_model_types_h_\n#define Ctypes_model_types_h_\n#endif\n",
+    )
+    .unwrap();
+    let headers: Vec<PathBuf> = ["Ctypes_model.h", "Ctypes_model_types.h"]
+        .iter()
+        .map(|header| sys_dir.join(header))
+        .collect();
+    env::set_var("OUT_DIR", &out_dir);
+    env::remove_var("CARGO_FEATURE_PREGENERATED");
+
+    // online: the module is written into the pre-generated files directory
+    let sys = Sys::builder()
+        .header(&headers[0])
+        .header(&headers[1])
+        .generated_dir(&generated_dir)
+        .build();
+    sys.generate_module();
+    let online = fs::read_to_string(out_dir.join("controller.rs")).unwrap();
+    let digest = digest_line(&generated_dir);
+    assert!(digest.starts_with("// simulink-rs digest: "));
+
+    // the digest does not depend on the order of the headers
+    let other_dir = tmp.join("other");
+    Sys::builder()
+        .header(&headers[1])
+        .header(&headers[0])
+        .generated_dir(&other_dir)
+        .build()
+        .generate_module();
+    assert_eq!(digest_line(&other_dir), digest);
+
+    // but it depends on the builder settings
+    Sys::builder()
+        .header(&headers[0])
+        .header(&headers[1])
+        .define("NUMST", Some("1"))
+        .generated_dir(&other_dir)
+        .build()
+        .generate_module();
+    assert_ne!(digest_line(&other_dir), digest);
+
+    // offline: the module is read back from the pre-generated files directory
+    env::set_var("CARGO_FEATURE_PREGENERATED", "1");
+    fs::remove_file(out_dir.join("controller.rs")).unwrap();
+    sys.generate_module();
+    let offline = fs::read_to_string(out_dir.join("controller.rs")).unwrap();
+    assert_eq!(offline, format!("{}\n{}", digest, online));
+
+    // a stale pre-generated file fails the build
+    let stale = offline.replacen(&digest, "// simulink-rs digest: 0000000000000000", 1);
+    fs::write(generated_dir.join("controller.rs"), stale).unwrap();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| sys.generate_module()));
+    env::remove_var("CARGO_FEATURE_PREGENERATED");
+    fs::remove_dir_all(&tmp).unwrap();
+    assert!(result.is_err());
+}
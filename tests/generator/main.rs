@@ -0,0 +1,67 @@
+use simulink_rs::{model::Model, Generator, Sys, Template};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn root() -> PathBuf {
+    Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("tests")
+}
+
+struct Names;
+impl Generator for Names {
+    fn file_name(&self) -> String {
+        "names.rs".into()
+    }
+    fn generate(&self, model: &Model) -> String {
+        format!("pub const MODEL: &str = {:?};\n", model.name)
+    }
+}
+
+#[test]
+fn template() {
+    let sys = Sys::builder()
+        .folder(root().join("model").to_str().unwrap())
+        .build();
+    let template = fs::read_to_string(root().join("generator").join("signals.rs.tpl")).unwrap();
+    assert_eq!(
+        Template::render(&template, &sys.model()),
+        r#"/// Signals of the Gain_model model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// <Root>/u
+    u = 0,
+}
+impl Signal {
+    pub const SIZES: [usize; 1] = [3];
+}
+"#
+    );
+}
+
+#[test]
+#[should_panic(expected = "unknown placeholder \"name\" in template")]
+fn unknown_placeholder() {
+    Template::render("{{name}}", &Model::default());
+}
+
+#[test]
+fn generate() {
+    let out_dir = env::temp_dir().join(format!("simulink-rs-generator-{}", std::process::id()));
+    fs::create_dir_all(&out_dir).unwrap();
+    env::set_var("OUT_DIR", &out_dir);
+    let sys = Sys::builder()
+        .folder(root().join("model").to_str().unwrap())
+        .generator(Names)
+        .template(root().join("generator").join("signals.rs.tpl"))
+        .build();
+    assert!(format!("{:?}", sys).contains(r#"generators: ["names.rs", "signals.rs"]"#));
+    sys.generate();
+    assert_eq!(
+        fs::read_to_string(out_dir.join("names.rs")).unwrap(),
+        "pub const MODEL: &str = \"Gain_model\";\n"
+    );
+    assert!(fs::read_to_string(out_dir.join("signals.rs"))
+        .unwrap()
+        .contains("u = 0,"));
+    fs::remove_dir_all(&out_dir).unwrap();
+}
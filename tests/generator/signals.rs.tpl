@@ -0,0 +1,9 @@
+/// Signals of the {{model}} model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+{{#inputs}}    /// {{block_path}}
+    {{name}} = {{index}},
+{{/inputs}}}
+impl Signal {
+    pub const SIZES: [usize; 1] = [{{#inputs}}{{size}}{{/inputs}}];
+}
@@ -0,0 +1,165 @@
+use simulink_rs::{Change, Group, Interface, InterfaceLock, Sys};
+use std::env;
+use std::path::{Path, PathBuf};
+
+fn sys(lock: InterfaceLock, lockfile: &str) -> Sys {
+    let path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("model");
+    Sys::builder()
+        .folder(path.to_str().unwrap())
+        .lock_interface(lock)
+        .lockfile(lockfile_path(lockfile))
+        .build()
+}
+
+fn lockfile_path(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("simulink-rs-{}-{}.lock", name, std::process::id()))
+}
+
+#[test]
+fn update_and_check() {
+    let sys = sys(InterfaceLock::Update, "update");
+    sys.lock_interface();
+    let interface = Interface::read(sys.lockfile()).unwrap();
+    assert_eq!(interface, sys.interface());
+    assert_eq!(interface.model, "Gain_model");
+    assert_eq!(interface.model_version.as_deref(), Some("1.2"));
+    assert_eq!(interface.inputs[0].to_string(), "u: real_T[3]");
+    assert_eq!(interface.parameters.len(), 2);
+
+    let sys = self::sys(InterfaceLock::Check, "update");
+    sys.lock_interface();
+
+    // the lockfile is not rewritten if the interface has not changed
+    let modified = || {
+        std::fs::metadata(sys.lockfile())
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+    let before = modified();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    self::sys(InterfaceLock::Update, "update").lock_interface();
+    assert_eq!(modified(), before);
+}
+
+#[test]
+#[should_panic(expected = "inputs: u resized from 4 to 3 (breaking)")]
+fn check_resized() {
+    let sys = sys(InterfaceLock::Check, "resized");
+    let mut interface = sys.interface();
+    interface.inputs[0].size = Some(4);
+    interface.write(sys.lockfile()).unwrap();
+    sys.lock_interface();
+}
+
+#[test]
+fn hash() {
+    let sys = sys(InterfaceLock::Off, "hash");
+    let interface = sys.interface();
+    let mut other = interface.clone();
+    other.model_version = Some("1.3".into());
+    assert!(interface.matches(&other));
+    assert_eq!(interface.hash(), other.hash());
+    other.states[0].dtype = "real32_T".into();
+    assert!(!interface.matches(&other));
+    assert_ne!(interface.hash(), other.hash());
+    assert_eq!(interface.hash(), sys.model().interface_hash());
+    assert!(!sys.lockfile().exists());
+}
+
+#[test]
+fn diff() {
+    let old = sys(InterfaceLock::Off, "diff").interface();
+    let mut new = old.clone();
+    new.inputs[0].size = None;
+    new.outputs[0].dtype = "real32_T".into();
+    new.states[0].name = "Delay_states".into();
+    new.parameters.swap(0, 1);
+    new.parameters.remove(1);
+    let changes = old.diff(&new);
+    assert_eq!(
+        changes,
+        vec![
+            Change::Resized {
+                group: Group::Inputs,
+                name: "u".into(),
+                from: Some(3),
+                to: None
+            },
+            Change::DtypeChanged {
+                group: Group::Outputs,
+                name: "y".into(),
+                from: "real_T".into(),
+                to: "real32_T".into()
+            },
+            Change::Renamed {
+                group: Group::States,
+                from: "Delay_DSTATE".into(),
+                to: "Delay_states".into(),
+                block_path: "<Root>/Delay".into()
+            },
+            Change::Removed {
+                group: Group::Parameters,
+                port: old.parameters[0].clone()
+            },
+        ]
+    );
+    assert_eq!(
+        changes.iter().map(|c| c.is_breaking()).collect::<Vec<_>>(),
+        vec![true, true, true, true]
+    );
+    assert_eq!(changes[0].to_string(), "inputs: u resized from 3 to scalar");
+
+    let mut new = old.clone();
+    new.parameters.swap(0, 1);
+    new.inputs.push(new.outputs[0].clone());
+    assert_eq!(
+        old.diff(&new),
+        vec![
+            Change::Added {
+                group: Group::Inputs,
+                port: old.outputs[0].clone()
+            },
+            Change::Reordered {
+                group: Group::Parameters
+            },
+        ]
+    );
+    assert_eq!(
+        old.diff(&new)
+            .iter()
+            .map(|c| c.is_breaking())
+            .collect::<Vec<_>>(),
+        vec![true, true]
+    );
+
+    // the parameters types are in the bindings, only an added parameter is not breaking
+    let mut new = old.clone();
+    new.parameters.remove(1);
+    let changes = new.diff(&old);
+    assert_eq!(
+        changes,
+        vec![Change::Added {
+            group: Group::Parameters,
+            port: old.parameters[1].clone()
+        }]
+    );
+    assert!(!changes[0].is_breaking());
+    assert!(old.diff(&new)[0].is_breaking());
+
+    // the order of the inputs sets the layout of the flattened inputs and of the wire format
+    let mut new = old.clone();
+    new.inputs.push(new.outputs[0].clone());
+    let mut reordered = new.clone();
+    reordered.inputs.swap(0, 1);
+    let changes = new.diff(&reordered);
+    assert_eq!(
+        changes,
+        vec![Change::Reordered {
+            group: Group::Inputs
+        }]
+    );
+    assert!(changes[0].is_breaking());
+}
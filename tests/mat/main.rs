@@ -0,0 +1,152 @@
+#![cfg(feature = "mat")]
+
+use serde::{Deserialize, Serialize};
+use simulink_rs::mat::{MatArray, MatClass, MatData, MatError, MatFile};
+
+fn check(mat: &MatFile) {
+    let names: Vec<_> = mat.arrays.iter().map(|array| array.name.as_str()).collect();
+    assert_eq!(names, ["inputs", "gain", "count", "flags", "cells", "z"]);
+
+    let inputs = mat.get("inputs").unwrap();
+    assert_eq!(
+        inputs.field("LC_FxyzMxyz_CG").unwrap().real().unwrap(),
+        [1., 2., 3., 4., 5., 6.]
+    );
+    assert_eq!(
+        inputs.field("comment").unwrap().data,
+        MatData::Char("logged".into())
+    );
+    let sa = inputs.field("SA_offsetF_cmd").unwrap();
+    assert_eq!(sa.dims, [335, 1]);
+    assert_eq!(sa.real().unwrap()[..8], [0., 1., 2., 3., 4., 5., 6., 0.]);
+
+    let gain = mat.get("gain").unwrap();
+    assert_eq!(gain.dims, [2, 3]);
+    assert_eq!(gain.real().unwrap(), [1., 4., 2., 5., 3., 6.]);
+    assert_eq!(mat.deserialize::<i32>("count").unwrap(), 42);
+    assert_eq!(
+        mat.get("flags").unwrap().data,
+        MatData::Numeric {
+            class: MatClass::Logical,
+            real: vec![1., 0., 1.],
+            imag: None
+        }
+    );
+    assert_eq!(
+        mat.deserialize::<[bool; 3]>("flags").unwrap(),
+        [true, false, true]
+    );
+    let MatData::Cell(cells) = &mat.get("cells").unwrap().data else {
+        panic!("expected a cell array")
+    };
+    assert_eq!(cells[0].data, MatData::Char("a".into()));
+    assert_eq!(cells[1].real().unwrap(), [1., 2.]);
+    let MatData::Numeric { imag, .. } = &mat.get("z").unwrap().data else {
+        panic!("expected a numeric array")
+    };
+    assert_eq!(imag.as_deref(), Some(&[-1., 0.5][..]));
+}
+
+#[test]
+fn read() {
+    check(&MatFile::read("tests/mat/matlab.mat").unwrap());
+    check(&MatFile::read("tests/mat/matlab_be.mat").unwrap());
+}
+
+#[test]
+fn write() {
+    let mat = MatFile::read("tests/mat/matlab.mat").unwrap();
+    let path = std::env::temp_dir().join(format!("simulink-rs-{}.mat", std::process::id()));
+    mat.write(&path).unwrap();
+    let written = MatFile::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    check(&written);
+    assert_eq!(written, mat);
+
+    let mut mat = MatFile::default();
+    mat.insert(MatArray {
+        name: "text".into(),
+        dims: vec![2, 3],
+        data: MatData::Char("abc\nde".into()),
+    });
+    let mut bytes = vec![];
+    mat.to_writer(&mut bytes).unwrap();
+    let read = MatFile::from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(
+        read.get("text").unwrap().data,
+        MatData::Char("abc\nde ".into())
+    );
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Inputs {
+    #[serde(rename = "LC_FxyzMxyz_CG")]
+    lc: Vec<f64>,
+    #[serde(rename = "SA_offsetF_cmd")]
+    sa: Vec<u8>,
+}
+
+#[test]
+fn serde() {
+    let mat = MatFile::read("tests/mat/matlab.mat").unwrap();
+    let inputs: Inputs = mat.deserialize("inputs").unwrap();
+    assert_eq!(inputs.lc, [1., 2., 3., 4., 5., 6.]);
+    assert_eq!(inputs.sa.len(), 335);
+    assert!(matches!(
+        mat.deserialize::<Inputs>("outputs"),
+        Err(MatError::NotFound(_))
+    ));
+    assert!(matches!(
+        mat.deserialize::<f64>("gain"),
+        Err(MatError::Serde(_))
+    ));
+    assert!(mat.deserialize::<Vec<u8>>("z").is_ok());
+    assert!(mat.deserialize::<Vec<i8>>("gain").is_ok());
+
+    let mut mat = MatFile::default();
+    mat.serialize("inputs", &inputs).unwrap();
+    let array = mat.get("inputs").unwrap();
+    let lc = array.field("LC_FxyzMxyz_CG").unwrap();
+    assert_eq!(lc.dims, [6, 1]);
+    assert_eq!(
+        array.field("SA_offsetF_cmd").unwrap().data,
+        MatData::Numeric {
+            class: MatClass::Uint8,
+            real: inputs.sa.iter().map(|&v| v as f64).collect(),
+            imag: None
+        }
+    );
+    let mut bytes = vec![];
+    mat.to_writer(&mut bytes).unwrap();
+    let mat = MatFile::from_reader(bytes.as_slice()).unwrap();
+    assert_eq!(mat.deserialize::<Inputs>("inputs").unwrap(), inputs);
+}
+
+#[test]
+fn dims_overflow() {
+    let mut mat = MatFile::default();
+    mat.insert(MatArray::double("x", vec![3, 5, 7], vec![0.; 105]));
+    let mut bytes = vec![];
+    mat.to_writer(&mut bytes).unwrap();
+    // dimensions of 2^31 - 1 elements each
+    let dims: Vec<u8> = [3i32, 5, 7]
+        .iter()
+        .flat_map(|dim| dim.to_le_bytes())
+        .collect();
+    let i = bytes.windows(12).position(|window| window == dims).unwrap();
+    for dim in bytes[i..i + 12].chunks_mut(4) {
+        dim.copy_from_slice(&i32::MAX.to_le_bytes());
+    }
+    assert!(matches!(
+        MatFile::from_reader(bytes.as_slice()),
+        Err(MatError::Format(_))
+    ));
+
+    let mut mat = MatFile::default();
+    mat.insert(MatArray {
+        name: "cells".into(),
+        dims: vec![usize::MAX; 2],
+        data: MatData::Cell(vec![]),
+    });
+    assert!(mat.to_writer(&mut vec![]).is_err());
+}
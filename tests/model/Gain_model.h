@@ -0,0 +1,56 @@
+/*
+ * File: Gain_model.h
+ *
+ * Code generated for Simulink model 'Gain_model'.
+ *
+ * Model version                  : 1.2
+ * Simulink Coder version         : 9.8 (R2022b) 13-May-2022
+ * C/C++ source code generated on : Mon May 15 10:12:31 2023
+ *
+ * Target selection: ert.tlc
+ */
+
+#ifndef RTW_HEADER_Gain_model_h_
+#define RTW_HEADER_Gain_model_h_
+#include "rtwtypes.h"
+#include "Gain_model_types.h"
+
+/* External inputs (root inport signals with default storage) */
+typedef struct {
+  real_T u[3];                         /* '<Root>/u' */
+} ExtU_Gain_model_T;
+
+/* External outputs (root outports fed by signals with default storage) */
+typedef struct {
+  real_T y[3];                         /* '<Root>/y' */
+} ExtY_Gain_model_T;
+
+/* Block states (default storage) for system '<Root>' */
+typedef struct {
+  real_T Delay_DSTATE[3];              /* '<Root>/Delay' */
+} DW_Gain_model_T;
+
+/* Parameters (default storage) */
+struct P_Gain_model_T_ {
+  real_T Gain_Gain;                    /* Expression: 2
+                                        * Referenced by: '<Root>/Gain'
+                                        */
+  real_T Delay_InitialCondition[3];    /* Expression: [0 0 0]
+                                        * Referenced by: '<Root>/Delay'
+                                        */
+};
+
+/* Real-time Model Data Structure */
+struct tag_RTM_Gain_model_T {
+  DW_Gain_model_T *dwork;
+};
+
+/* Block parameters (default storage) */
+extern P_Gain_model_T Gain_model_P;
+
+/* Model entry point functions */
+extern void Gain_model_initialize(RT_MODEL_Gain_model_T *const Gain_model_M);
+extern void Gain_model_step(RT_MODEL_Gain_model_T *const Gain_model_M,
+  ExtU_Gain_model_T *Gain_model_U, ExtY_Gain_model_T *Gain_model_Y);
+
+#endif                                 /* RTW_HEADER_Gain_model_h_ */
@@ -0,0 +1,10 @@
+#ifndef RTW_HEADER_Gain_model_types_h_
+#define RTW_HEADER_Gain_model_types_h_
+
+/* Parameters (default storage) */
+typedef struct P_Gain_model_T_ P_Gain_model_T;
+
+/* Forward declaration for rtModel */
+typedef struct tag_RTM_Gain_model_T RT_MODEL_Gain_model_T;
+
+#endif                                 /* RTW_HEADER_Gain_model_types_h_ */
@@ -0,0 +1,14 @@
+#include "Gain_model.h"
+
+int_T main(int_T argc, const char *argv[])
+{
+  Gain_model_initialize(Gain_model_M);
+
+  /* Attach rt_OneStep to a timer or interrupt service routine with
+   * period 0.001 seconds (base rate of the model) here.
+   * The call syntax for rt_OneStep is
+   *
+   *  rt_OneStep();
+   */
+  return 0;
+}
@@ -0,0 +1,278 @@
+use simulink_model::Model;
+use std::env;
+use std::path::Path;
+
+fn model() -> Model {
+    let path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("model");
+    let (headers, sources) = simulink_model::c_files(&path, false);
+    let header = simulink_model::main_header(&headers, &sources).unwrap();
+    Model::from_files(header, &headers, &sources).unwrap()
+}
+
+#[test]
+fn interface() {
+    let model = model();
+    assert_eq!(model.name, "Gain_model");
+    assert_eq!(model.inputs().unwrap().name, "ExtU_Gain_model_T");
+    assert_eq!(model.outputs().unwrap().name, "ExtY_Gain_model_T");
+    assert_eq!(model.states().unwrap().name, "DW_Gain_model_T");
+    let parameters = model.parameters().unwrap();
+    assert_eq!(parameters.name, "P_Gain_model_T");
+    let properties: Vec<_> = parameters
+        .properties
+        .iter()
+        .map(|io| (io.name.as_str(), io.size))
+        .collect();
+    assert_eq!(
+        properties,
+        vec![("Gain_Gain", None), ("Delay_InitialCondition", Some(3))]
+    );
+}
+
+#[test]
+fn metadata() {
+    let model = model();
+    assert_eq!(model.metadata.model_version.as_deref(), Some("1.2"));
+    assert_eq!(
+        model.metadata.coder_version.as_deref(),
+        Some("9.8 (R2022b) 13-May-2022")
+    );
+    assert_eq!(
+        model.metadata.generated_on.as_deref(),
+        Some("Mon May 15 10:12:31 2023")
+    );
+    assert_eq!(model.metadata.target.as_deref(), Some("ert.tlc"));
+    assert_eq!(model.sample_time, Some(0.001));
+    assert_eq!(
+        model.symbols.functions,
+        vec!["Gain_model_initialize", "Gain_model_step"]
+    );
+    assert_eq!(model.symbols.variables, vec!["Gain_model_P"]);
+}
+
+#[test]
+fn block_paths() {
+    let model = model();
+    let block_paths: Vec<_> = model
+        .simulink
+        .iter()
+        .flat_map(|simulink| simulink.properties.iter())
+        .map(|io| io.block_path.as_deref())
+        .collect();
+    assert_eq!(
+        block_paths,
+        vec![
+            Some("<Root>/u"),
+            Some("<Root>/y"),
+            Some("<Root>/Delay"),
+            Some("<Root>/Gain"),
+            Some("<Root>/Delay")
+        ]
+    );
+}
+
+#[test]
+fn parse_str() {
+    let path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("model")
+        .join("Gain_model.h");
+    let code = std::fs::read_to_string(&path).unwrap();
+    let model: Model = code.parse().unwrap();
+    assert_eq!(model.name, "Gain_model");
+    assert_eq!(model.simulink.len(), 4);
+    assert_eq!(model.sample_time, None);
+    let reader = std::io::BufReader::new(std::fs::File::open(&path).unwrap());
+    assert_eq!(Model::from_reader(reader).unwrap(), model);
+    assert!("/* no model */".parse::<Model>().is_err());
+}
+
+#[test]
+fn serde() {
+    let path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("model");
+    let sys = simulink_rs::Sys::builder()
+        .folder(path.to_str().unwrap())
+        .keep_example_mains()
+        .build();
+    let model: simulink_rs::model::Model = sys.model();
+    assert_eq!(model, self::model());
+    let json = serde_json::to_string(&model).unwrap();
+    assert_eq!(serde_json::from_str::<Model>(&json).unwrap(), model);
+}
+
+#[test]
+fn snake_case() {
+    assert_eq!(
+        simulink_model::snake_case("SA_offsetF_cmd"),
+        "sa_offset_f_cmd"
+    );
+    assert_eq!(simulink_model::snake_case("Res_Act_F"), "res_act_f");
+    assert_eq!(
+        simulink_model::snake_case("LC_FxyzMxyz_CG"),
+        "lc_fxyz_mxyz_cg"
+    );
+    assert_eq!(simulink_model::snake_case("M1SAOffset2"), "m1_sa_offset2");
+    assert_eq!(simulink_model::snake_case("step"), "step_");
+    assert_eq!(simulink_model::snake_case("Type"), "type_");
+    assert_eq!(simulink_model::snake_case("Self"), "self_");
+}
+
+#[test]
+fn keyword_accessors() {
+    let mut model = model();
+    let names = ["Type", "Mod", "Match", "Loop", "Ref", "Move", "Self"];
+    let ports = &mut model.simulink[0].properties;
+    let port = ports[0].clone();
+    ports.clear();
+    for name in names {
+        let mut port = port.clone();
+        port.name = name.into();
+        ports.push(port);
+    }
+    let code = simulink_model::pretty(model.wrapper(&Default::default()));
+    for name in names {
+        let snake = name.to_lowercase();
+        assert!(code.contains(&format!("pub fn {}_(&self)", snake)));
+        assert!(code.contains(&format!("pub fn set_{}(", snake)));
+    }
+}
+
+#[test]
+fn plain_c_types() {
+    let mut model = model();
+    model.simulink[0].properties[0].dtype = "double".into();
+    model.simulink[1].properties[0].dtype = "int".into();
+    let code = simulink_model::pretty(model.wrapper(&Default::default()));
+    assert!(code.contains("pub fn u(&self) -> &[f64]"));
+    assert!(code.contains("pub fn y(&self) -> &[i32]"));
+    assert!(code.contains("*value = <i32>::from_le_bytes("));
+    assert!(code.contains("*value = *x as f64;"));
+}
+
+#[test]
+fn scalar_accessors() {
+    let mut model = model();
+    model.simulink[0].properties[0].size = None;
+    let code = simulink_model::pretty(model.wrapper(&Default::default()));
+    assert!(code
+        .contains("pub fn u(&self) -> &[f64] {\n        ::std::slice::from_ref(&self.inputs.u)"));
+    assert!(code.contains("self.inputs.u = value[0];"));
+    assert!(code.contains("#[doc(alias = \"u\")]\n    pub fn set_u("));
+    assert!(!code.contains("#[doc(alias = \"u\")]\n    pub fn u("));
+}
+
+#[test]
+fn flat_numeric() {
+    let inputs_len = |model: &Model| {
+        let code = simulink_model::pretty(model.wrapper(&Default::default()));
+        let start = code
+            .find("impl ::simulink_rs::Flatten for ExtU_Gain_model_T {")
+            .unwrap();
+        let len = &code[start..];
+        let len = &len[len.find("const LEN: usize = ").unwrap() + 19..];
+        (len[..len.find(';').unwrap()].to_string(), code)
+    };
+    let mut model = model();
+    model.simulink[0].properties[0].dtype = "boolean_T".into();
+    let (len, code) = inputs_len(&model);
+    assert!(code.contains("*x = *value as u8;"));
+    assert_eq!(len, "3usize");
+    model.simulink[0].properties[0].dtype = "Bus_T".into();
+    assert_eq!(inputs_len(&model).0, "0usize");
+}
+
+#[test]
+fn reserved_methods() {
+    let model = model();
+    let code = simulink_model::pretty(model.wrapper(&Default::default()));
+    // methods of the wrapper and of its `SimulinkController` implementation
+    let re_fn = regex::Regex::new(r"(?m)^    (?:pub )?fn (\w+)").unwrap();
+    let mut methods: Vec<String> = code
+        .split("\nimpl ")
+        .filter(|block| block.starts_with("Gain_model {") || block.contains(" for Gain_model {"))
+        .flat_map(|block| re_fn.captures_iter(block).map(|caps| caps[1].to_string()))
+        .filter(|method| !["u", "y", "set_u"].contains(&method.as_str()))
+        .collect();
+    // methods of the `SimulinkController` trait
+    let controller = std::fs::read_to_string(
+        Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+            .join("src")
+            .join("controller.rs"),
+    )
+    .unwrap();
+    let controller = &controller[controller.find("pub trait SimulinkController").unwrap()..];
+    let controller = &controller[..controller.find("\n}").unwrap()];
+    methods.extend(
+        re_fn
+            .captures_iter(controller)
+            .map(|caps| caps[1].to_string()),
+    );
+    assert!(methods.contains(&"outputs_to_vec".to_string()));
+    assert!(methods.contains(&"restore".to_string()));
+    for method in methods {
+        assert_eq!(
+            simulink_model::snake_case(&method),
+            format!("{}_", method),
+            "{method}"
+        );
+    }
+}
+
+#[test]
+fn registry_names() {
+    let mut model = model();
+    // the snake case name of the input is the Simulink name of the output
+    model.simulink[0].properties[0].name = "Gain_In".into();
+    model.simulink[1].properties[0].name = "gain_in".into();
+    let code = simulink_model::pretty(model.wrapper(&Default::default()));
+    assert!(code.contains("name: \"Gain_In\","));
+    assert!(code.contains("name: \"gain_in\","));
+    // the snake case names are disambiguated as the accessors
+    assert!(code.contains("\"Gain_In\" | \"input_gain_in\" => Some("));
+    assert!(code.contains("\"gain_in\" | \"output_gain_in\" => Some("));
+    assert!(!code.contains("\"gain_in\" => Some("));
+}
+
+#[test]
+fn colliding_accessors() {
+    let mut model = model();
+    // an input and an output with the same snake case name
+    model.simulink[0].properties[0].name = "u".into();
+    model.simulink[1].properties[0].name = "U".into();
+    let code = simulink_model::pretty(model.wrapper(&Default::default()));
+    assert!(code.contains("pub fn input_u(&self)"));
+    assert!(code.contains("pub fn set_input_u("));
+    assert!(code.contains("pub fn output_u(&self)"));
+    assert!(!code.contains("pub fn u(&self)"));
+    assert!(code.contains("pub const INPUT_U_OFFSET: usize"));
+    assert!(code.contains("pub const OUTPUT_U_OFFSET: usize"));
+    assert!(code.contains("\"u\" | \"input_u\" => Some("));
+    assert!(code.contains("\"U\" | \"output_u\" => Some("));
+    assert!(code.contains("\"u\" | \"input_u\" => self.set_input_u(value),"));
+    // the methods and the constants of the wrapper are unique
+    let re_item = regex::Regex::new(r"(?m)^    pub (?:fn|const) (\w+)").unwrap();
+    let mut items: Vec<_> = code
+        .split("\nimpl ")
+        .filter(|block| block.starts_with("Gain_model {"))
+        .flat_map(|block| re_item.captures_iter(block).map(|caps| caps[1].to_string()))
+        .collect();
+    let len = items.len();
+    items.sort();
+    items.dedup();
+    assert_eq!(items.len(), len);
+
+    // two inputs with the same snake case name
+    let mut input = model.simulink[0].properties[0].clone();
+    input.name = "U".into();
+    model.simulink[0].properties.push(input);
+    model.simulink[1].properties[0].name = "y".into();
+    let code = simulink_model::pretty(model.wrapper(&Default::default()));
+    assert!(code.contains("pub fn u(&self)"));
+    assert!(code.contains("pub fn u_2(&self)"));
+    assert!(code.contains("pub fn set_u_2("));
+    assert!(code.contains("pub const U_2_OFFSET: usize"));
+}
@@ -6,12 +6,21 @@
     pub SA_offsetF_cmd: [real_T; 306],
 } */
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ExtU_M1SA_Control_CS_T {
     pub LC_FxyzMxyz_CG: [f64; 2],
     pub SA_offsetF_cmd: [f64; 306],
     pub scalar: f64,
 }
+impl Default for ExtU_M1SA_Control_CS_T {
+    fn default() -> Self {
+        Self {
+            LC_FxyzMxyz_CG: [Default::default(); 2],
+            SA_offsetF_cmd: [Default::default(); 306],
+            scalar: Default::default(),
+        }
+    }
+}
 impl ::serde::ser::Serialize for ExtU_M1SA_Control_CS_T {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -21,147 +30,186 @@ impl ::serde::ser::Serialize for ExtU_M1SA_Control_CS_T {
         ::serde::ser::SerializeStruct::serialize_field(
             &mut serde_state,
             "LC_FxyzMxyz_CG",
-            &Vec::from(&self.LC_FxyzMxyz_CG),
+            &self.LC_FxyzMxyz_CG[..],
         )?;
         ::serde::ser::SerializeStruct::serialize_field(
             &mut serde_state,
             "SA_offsetF_cmd",
-            &Vec::from(&self.SA_offsetF_cmd),
+            &self.SA_offsetF_cmd[..],
         )?;
         ::serde::ser::SerializeStruct::serialize_field(&mut serde_state, "scalar", &self.scalar)?;
         ::serde::ser::SerializeStruct::end(serde_state)
     }
 }
 
-impl<'de> ::serde::de::Deserialize<'de> for ExtU_M1SA_Control_CS_T {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: ::serde::de::Deserializer<'de>,
-    {
-        const FIELDS: &'static [&'static str] = &["LC_FxyzMxyz_CG", "SA_offsetF_cmd", "scalar"];
+const _: () = {
+    const FIELDS: &[&str] = &["LC_FxyzMxyz_CG", "SA_offsetF_cmd", "scalar"];
 
-        enum Field {
-            LC_FxyzMxyz_CG,
-            SA_offsetF_cmd,
-            Scalar,
-        }
+    enum Field {
+        LC_FxyzMxyz_CG,
+        SA_offsetF_cmd,
+        Scalar,
+    }
 
-        impl<'de> ::serde::de::Deserialize<'de> for Field {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: ::serde::de::Deserializer<'de>,
-            {
-                struct FieldVisitor;
-                impl<'de> ::serde::de::Visitor<'de> for FieldVisitor {
-                    type Value = Field;
-                    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                        formatter.write_str("field identifier")
-                    }
+    impl<'de> ::serde::de::Deserialize<'de> for Field {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: ::serde::de::Deserializer<'de>,
+        {
+            struct FieldVisitor;
+            impl<'de> ::serde::de::Visitor<'de> for FieldVisitor {
+                type Value = Field;
+                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    formatter.write_str("field identifier")
+                }
 
-                    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
-                    where
-                        E: ::serde::de::Error,
-                    {
-                        match value {
-                            "LC_FxyzMxyz_CG" => Ok(Field::LC_FxyzMxyz_CG),
-                            "SA_offsetF_cmd" => Ok(Field::SA_offsetF_cmd),
-                            "scalar" => Ok(Field::Scalar),
-                            _ => Err(::serde::de::Error::unknown_field(value, FIELDS)),
-                        }
+                fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                where
+                    E: ::serde::de::Error,
+                {
+                    match value {
+                        "LC_FxyzMxyz_CG" => Ok(Field::LC_FxyzMxyz_CG),
+                        "SA_offsetF_cmd" => Ok(Field::SA_offsetF_cmd),
+                        "scalar" => Ok(Field::Scalar),
+                        _ => Err(::serde::de::Error::unknown_field(value, FIELDS)),
                     }
                 }
-                deserializer.deserialize_identifier(FieldVisitor)
             }
+            deserializer.deserialize_identifier(FieldVisitor)
         }
+    }
 
-        struct SimulinkVisitor;
-        impl<'de> ::serde::de::Visitor<'de> for SimulinkVisitor {
-            type Value = ExtU_M1SA_Control_CS_T;
+    /// Sets the fields of the structure in place,
+    /// the missing fields keep their values if `partial` is `true`
+    struct SimulinkVisitor<'a> {
+        simulink: &'a mut ExtU_M1SA_Control_CS_T,
+        partial: bool,
+    }
+    impl<'de, 'a> ::serde::de::Visitor<'de> for SimulinkVisitor<'a> {
+        type Value = ();
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("struct ExtU_M1SA_Control_CS_T")
+        }
 
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("struct ExtU_M1SA_Control_CS_T")
+        fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+        where
+            V: ::serde::de::SeqAccess<'de>,
+        {
+            if seq
+                .next_element_seed(::simulink_rs::ArraySeed(&mut self.simulink.LC_FxyzMxyz_CG))?
+                .is_none()
+            {
+                return if self.partial {
+                    Ok(())
+                } else {
+                    Err(::serde::de::Error::invalid_length(0, &self))
+                };
             }
-
-            fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
-            where
-                V: ::serde::de::SeqAccess<'de>,
+            if seq
+                .next_element_seed(::simulink_rs::ArraySeed(&mut self.simulink.SA_offsetF_cmd))?
+                .is_none()
             {
-                let LC_FxyzMxyz_CG: [f64; 2] = seq
-                    .next_element::<Vec<f64>>()?
-                    .ok_or_else(|| ::serde::de::Error::invalid_length(2, &self))?
-                    .try_into()
-                    .map_err(|_| {
-                        ::serde::de::Error::invalid_value(::serde::de::Unexpected::Seq, &self)
-                    })?;
-                let SA_offsetF_cmd: [f64; 306] = seq
-                    .next_element::<Vec<f64>>()?
-                    .ok_or_else(|| ::serde::de::Error::invalid_length(306, &self))?
-                    .try_into()
-                    .map_err(|_| {
-                        ::serde::de::Error::invalid_value(::serde::de::Unexpected::Seq, &self)
-                    })?;
-                let scalar = seq
-                    .next_element::<f64>()?
-                    .ok_or_else(|| ::serde::de::Error::invalid_length(1, &self))?;
-                Ok(ExtU_M1SA_Control_CS_T {
-                    LC_FxyzMxyz_CG,
-                    SA_offsetF_cmd,
-                    scalar,
-                })
+                return if self.partial {
+                    Ok(())
+                } else {
+                    Err(::serde::de::Error::invalid_length(1, &self))
+                };
             }
-
-            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
-            where
-                A: ::serde::de::MapAccess<'de>,
+            if seq
+                .next_element()?
+                .map(|value| self.simulink.scalar = value)
+                .is_none()
             {
-                let mut LC_FxyzMxyz_CG = None;
-                let mut SA_offsetF_cmd = None;
-                let mut scalar = None;
-                while let Some(key) = map.next_key::<Field>()? {
-                    match key {
-                        Field::LC_FxyzMxyz_CG => {
-                            if LC_FxyzMxyz_CG.is_some() {
-                                return Err(::serde::de::Error::duplicate_field("LC_FxyzMxyz_CG"));
-                            }
-                            LC_FxyzMxyz_CG = Some(map.next_value::<Vec<f64>>()?);
+                return if self.partial {
+                    Ok(())
+                } else {
+                    Err(::serde::de::Error::invalid_length(2, &self))
+                };
+            }
+            Ok(())
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: ::serde::de::MapAccess<'de>,
+        {
+            let mut seen = [false; 3];
+            while let Some(key) = map.next_key::<Field>()? {
+                match key {
+                    Field::LC_FxyzMxyz_CG => {
+                        if seen[0] {
+                            return Err(::serde::de::Error::duplicate_field("LC_FxyzMxyz_CG"));
                         }
-                        Field::SA_offsetF_cmd => {
-                            if SA_offsetF_cmd.is_some() {
-                                return Err(::serde::de::Error::duplicate_field("SA_offsetF_cmd"));
-                            }
-                            SA_offsetF_cmd = Some(map.next_value::<Vec<f64>>()?);
+                        map.next_value_seed(::simulink_rs::ArraySeed(
+                            &mut self.simulink.LC_FxyzMxyz_CG,
+                        ))?;
+                        seen[0] = true;
+                    }
+                    Field::SA_offsetF_cmd => {
+                        if seen[1] {
+                            return Err(::serde::de::Error::duplicate_field("SA_offsetF_cmd"));
                         }
-                        Field::Scalar => {
-                            if scalar.is_some() {
-                                return Err(::serde::de::Error::duplicate_field("scalar"));
-                            }
-                            scalar = Some(map.next_value::<f64>()?);
+                        map.next_value_seed(::simulink_rs::ArraySeed(
+                            &mut self.simulink.SA_offsetF_cmd,
+                        ))?;
+                        seen[1] = true;
+                    }
+                    Field::Scalar => {
+                        if seen[2] {
+                            return Err(::serde::de::Error::duplicate_field("scalar"));
                         }
+                        self.simulink.scalar = map.next_value()?;
+                        seen[2] = true;
                     }
                 }
-                let LC_FxyzMxyz_CG: [f64; 2] = LC_FxyzMxyz_CG
-                    .ok_or_else(|| ::serde::de::Error::missing_field("LC_FxyzMxyz_CG"))?
-                    .try_into()
-                    .map_err(|_| {
-                        ::serde::de::Error::invalid_value(::serde::de::Unexpected::Seq, &self)
-                    })?;
-                let SA_offsetF_cmd: [f64; 306] = SA_offsetF_cmd
-                    .ok_or_else(|| ::serde::de::Error::missing_field("SA_offsetF_cmd"))?
-                    .try_into()
-                    .map_err(|_| {
-                        ::serde::de::Error::invalid_value(::serde::de::Unexpected::Seq, &self)
-                    })?;
-                let scalar = scalar.ok_or_else(|| ::serde::de::Error::missing_field("scalar"))?;
-                Ok(ExtU_M1SA_Control_CS_T {
-                    LC_FxyzMxyz_CG,
-                    SA_offsetF_cmd,
-                    scalar,
-                })
             }
+            if !self.partial {
+                if let Some(i) = seen.iter().position(|seen| !seen) {
+                    return Err(::serde::de::Error::missing_field(FIELDS[i]));
+                }
+            }
+            Ok(())
         }
-        deserializer.deserialize_struct("ExtU_M1SA_Control_CS_T", FIELDS, SimulinkVisitor)
     }
-}
+
+    impl<'de> ::serde::de::Deserialize<'de> for ExtU_M1SA_Control_CS_T {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: ::serde::de::Deserializer<'de>,
+        {
+            let mut simulink = <ExtU_M1SA_Control_CS_T as Default>::default();
+            deserializer.deserialize_struct(
+                "ExtU_M1SA_Control_CS_T",
+                FIELDS,
+                SimulinkVisitor {
+                    simulink: &mut simulink,
+                    partial: false,
+                },
+            )?;
+            Ok(simulink)
+        }
+    }
+
+    impl ::simulink_rs::Patch for ExtU_M1SA_Control_CS_T {
+        fn apply_patch<'de, D>(&mut self, deserializer: D) -> Result<(), D::Error>
+        where
+            D: ::serde::de::Deserializer<'de>,
+        {
+            // the patch is applied to a copy, so that the structure is unchanged on error
+            let mut patched = *self;
+            deserializer.deserialize_struct(
+                "ExtU_M1SA_Control_CS_T",
+                FIELDS,
+                SimulinkVisitor {
+                    simulink: &mut patched,
+                    partial: true,
+                },
+            )?;
+            *self = patched;
+            Ok(())
+        }
+    }
+};
 
 #[test]
 fn main() {
@@ -176,3 +224,73 @@ fn main() {
     println!("{:?}", deserialized);
     assert_eq!(data, deserialized);
 }
+
+#[test]
+fn length() {
+    let data = ExtU_M1SA_Control_CS_T {
+        LC_FxyzMxyz_CG: [1.0, 2.0],
+        ..Default::default()
+    };
+    let mut json = serde_json::to_value(data).unwrap();
+    json["LC_FxyzMxyz_CG"] = serde_json::json!([1.0, 2.0, 3.0]);
+    let error = serde_json::from_value::<ExtU_M1SA_Control_CS_T>(json.clone()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid length 3, expected an array of 2 elements"
+    );
+    json["LC_FxyzMxyz_CG"] = serde_json::json!([1.0]);
+    let error = serde_json::from_value::<ExtU_M1SA_Control_CS_T>(json.clone()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid length 1, expected an array of 2 elements"
+    );
+    json.as_object_mut().unwrap().remove("LC_FxyzMxyz_CG");
+    let error = serde_json::from_value::<ExtU_M1SA_Control_CS_T>(json).unwrap_err();
+    assert_eq!(error.to_string(), "missing field `LC_FxyzMxyz_CG`");
+
+    let seq = serde_json::json!([[1.0, 2.0], vec![0.0; 306], 9.5]);
+    let deserialized: ExtU_M1SA_Control_CS_T = serde_json::from_value(seq).unwrap();
+    assert_eq!(deserialized.scalar, 9.5);
+    assert_eq!(deserialized.LC_FxyzMxyz_CG, data.LC_FxyzMxyz_CG);
+}
+
+#[test]
+fn patch() {
+    use simulink_rs::{Partial, Patch};
+
+    let mut data = ExtU_M1SA_Control_CS_T {
+        LC_FxyzMxyz_CG: [1.0, 2.0],
+        scalar: 9.5,
+        ..Default::default()
+    };
+    data.apply_patch(&mut serde_json::Deserializer::from_str(
+        r#"{"scalar": 1.5}"#,
+    ))
+    .unwrap();
+    assert_eq!(data.scalar, 1.5);
+    assert_eq!(data.LC_FxyzMxyz_CG, [1.0, 2.0]);
+    data.apply_patch(serde_json::json!([[3.0, 4.0]])).unwrap();
+    assert_eq!(data.LC_FxyzMxyz_CG, [3.0, 4.0]);
+    assert_eq!(data.scalar, 1.5);
+    let original = data;
+    assert!(data
+        .apply_patch(serde_json::json!({"LC_FxyzMxyz_CG": [1.0]}))
+        .is_err());
+    assert_eq!(data, original);
+    // the fields before the error are not applied either
+    assert!(data
+        .apply_patch(serde_json::json!({"scalar": 7.0, "LC_FxyzMxyz_CG": [1.0, 2.0, 3.0]}))
+        .is_err());
+    assert_eq!(data, original);
+
+    let Partial(partial) =
+        serde_json::from_str::<Partial<ExtU_M1SA_Control_CS_T>>(r#"{"scalar": 2.5}"#).unwrap();
+    assert_eq!(
+        partial,
+        ExtU_M1SA_Control_CS_T {
+            scalar: 2.5,
+            ..Default::default()
+        }
+    );
+    assert!(serde_json::from_str::<ExtU_M1SA_Control_CS_T>(r#"{"scalar": 2.5}"#).is_err());
+}
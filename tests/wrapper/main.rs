@@ -11,3 +11,18 @@ fn main() {
     dbg!(&sys);
     println!("{sys}");
 }
+
+#[test]
+fn generated_code() {
+    let path = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
+        .join("tests")
+        .join("wrapper");
+    let sys = Sys::builder()
+        .name("M1SA")
+        .folder(path.to_str().unwrap())
+        .build();
+    let code = sys.to_string();
+    assert!(code.contains("pub type M1SA = M1SA_Control_OA;"));
+    assert!(code.contains("pub struct M1SA_Control_OA {"));
+    assert!(code.contains("impl<'de> ::serde::de::Deserialize<'de> for ExtU_M1SA_Control_OA_T {"));
+}